@@ -161,6 +161,7 @@ impl SharePreviewApplication {
         self.set_accels_for_action("app.quit", &["<primary>q"]);
         self.set_accels_for_action("app.new-window", &["<primary>n"]);
         self.set_accels_for_action("win.url", &["<primary>l"]);
+        self.set_accels_for_action("win.reload", &["<primary><shift>r"]);
     }
 
     fn show_about_dialog(&self) {
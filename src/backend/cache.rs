@@ -0,0 +1,380 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    env,
+    fs,
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_std::io::{BufReader, Cursor, ReadExt};
+use surf::{
+    Body,
+    Client,
+    Request,
+    Response,
+    StatusCode,
+    http::{self, Method, headers},
+    middleware::{Middleware, Next},
+};
+use url::Url;
+
+use super::location::{self, ResponseUrl};
+
+/// Max size of a response body stored in the cache
+const MAX_ENTRY_SIZE: usize = 25e+6 as usize;
+/// Max size of all the bodies in the cache, the oldest are removed past it
+const MAX_CACHE_SIZE: u64 = 200e+6 as u64;
+
+/// Estimated size of the cached bodies, unknown until the first eviction scans them
+static CACHE_SIZE: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Disk cache for GET responses
+///
+/// Responses are keyed by URL and reused while fresh according to their
+/// Cache-Control header. Stale responses are revalidated with their ETag or
+/// Last-Modified validators.
+///
+/// Requests with a "Cache-Control: no-cache" header skip the cached response,
+/// the new one is still stored.
+///
+#[derive(Debug, Default)]
+pub struct HttpCache;
+
+#[surf::utils::async_trait]
+impl Middleware for HttpCache {
+    async fn handle(&self, mut req: Request, client: Client, next: Next<'_>) -> surf::Result<Response> {
+        if req.method() != Method::Get {
+            return next.run(req, client).await;
+        }
+
+        let path = entry_path(req.url());
+        let ranged = req.header("Range").is_some();
+        let reload = req.header(headers::CACHE_CONTROL)
+            .map(|value| value.last().as_str().contains("no-cache"))
+            .unwrap_or(false);
+
+        let entry = if reload { None } else { Entry::load(&path).await };
+
+        if let Some(entry) = &entry {
+            if entry.is_fresh() {
+                // Ranged requests also get the whole cached body
+                if let Ok(resp) = entry.response(&path).await {
+                    return Ok(resp);
+                }
+            } else if !ranged {
+                if let Some(etag) = &entry.etag {
+                    req.insert_header(headers::IF_NONE_MATCH, etag.as_str());
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    req.insert_header(headers::IF_MODIFIED_SINCE, last_modified.as_str());
+                }
+            }
+        }
+
+        let resp = next.run(req, client).await?;
+
+        match (resp.status(), entry) {
+            (StatusCode::NotModified, Some(mut entry)) => {
+                entry.revalidate(&resp);
+                if entry.save(&path, None).await.is_ok() {
+                    if let Ok(cached) = entry.response(&path).await {
+                        return Ok(cached);
+                    }
+                }
+                Ok(resp)
+            },
+            (StatusCode::Ok, _) => {
+                match Entry::from_response(&resp) {
+                    Some(entry) => store(entry, &path, resp).await,
+                    None => Ok(resp)
+                }
+            },
+            _ => Ok(resp)
+        }
+    }
+}
+
+/// Remove the cached response of a URL
+pub async fn remove(url: &Url) {
+    let path = entry_path(url);
+
+    for extension in ["meta", "body"] {
+        let _ = async_std::fs::remove_file(path.with_extension(extension)).await;
+    }
+}
+
+/// Count a stored body, evicting old entries the first time or once the cache may be full
+async fn track_size(len: u64) {
+    let previous = CACHE_SIZE.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |size| {
+        Some(size.saturating_add(len))
+    }).unwrap_or(u64::MAX);
+
+    if previous.saturating_add(len) > MAX_CACHE_SIZE {
+        // Only one eviction at a time, the others keep counting
+        if CACHE_SIZE.swap(0, Ordering::SeqCst) > MAX_CACHE_SIZE {
+            let total = async_std::task::spawn_blocking(evict).await;
+            CACHE_SIZE.fetch_add(total, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Remove the least recently stored entries until the cache fits in its max size
+///
+/// Returns the size of the bodies left in the cache.
+///
+fn evict() -> u64 {
+    let entries = match fs::read_dir(cache_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return 0
+    };
+
+    let mut bodies: Vec<(SystemTime, u64, PathBuf)> = entries.flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "body"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((metadata.modified().unwrap_or(UNIX_EPOCH), metadata.len(), entry.path()))
+        })
+        .collect();
+
+    let mut total: u64 = bodies.iter().map(|(_, len, _)| len).sum();
+    bodies.sort();
+
+    for (_, len, path) in bodies {
+        if total <= MAX_CACHE_SIZE {
+            break;
+        }
+
+        for extension in ["meta", "body"] {
+            let _ = fs::remove_file(path.with_extension(extension));
+        }
+        total = total.saturating_sub(len);
+    }
+
+    total
+}
+
+/// Save a response body to the cache, giving the response back with the same body
+async fn store(entry: Entry, path: &Path, mut resp: Response) -> surf::Result<Response> {
+    if let Some(len) = resp.len() {
+        if len > MAX_ENTRY_SIZE {
+            return Ok(resp);
+        }
+    }
+
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut body = resp.take_body().take(MAX_ENTRY_SIZE as u64 + 1);
+    body.read_to_end(&mut bytes).await?;
+
+    if bytes.len() > MAX_ENTRY_SIZE {
+        // Too big to be cached, put back what was read in front of the rest
+        let reader = BufReader::new(Cursor::new(bytes).chain(body.into_inner()));
+        resp.set_body(Body::from_reader(reader, None));
+    } else {
+        match entry.save(path, Some(&bytes)).await {
+            Ok(_) => track_size(bytes.len() as u64).await,
+            Err(err) => log::debug!("Couldn't save cache entry: {}", err)
+        }
+        resp.set_body(bytes);
+    }
+
+    Ok(resp)
+}
+
+/// Metadata of a cached response
+#[derive(Debug, Default)]
+struct Entry {
+    /// Seconds since the epoch when the response was stored or revalidated
+    stored: u64,
+    /// Seconds the response stays fresh
+    max_age: u64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_type: Option<String>,
+    /// URL the response was served from, after redirects
+    url: Option<String>,
+}
+
+impl Entry {
+    /// Create an entry for a response, or None if it can't be cached
+    fn from_response(resp: &Response) -> Option<Entry> {
+        let mut entry = Entry {
+            stored: now(),
+            etag: header(resp, headers::ETAG),
+            last_modified: header(resp, headers::LAST_MODIFIED),
+            content_type: header(resp, headers::CONTENT_TYPE),
+            url: location::response_url(resp).map(|url| url.to_string()),
+            ..Entry::default()
+        };
+
+        if !entry.set_max_age(resp) {
+            return None;
+        }
+
+        // Nothing to reuse without a lifetime or validators
+        if entry.max_age == 0 && entry.etag.is_none() && entry.last_modified.is_none() {
+            return None;
+        }
+
+        Some(entry)
+    }
+
+    /// Read the response lifetime from its Cache-Control header
+    ///
+    /// Returns false if the response must not be stored.
+    ///
+    fn set_max_age(&mut self, resp: &Response) -> bool {
+        self.max_age = 0;
+
+        if let Some(cache_control) = header(resp, headers::CACHE_CONTROL) {
+            for directive in cache_control.split(',').map(|d| d.trim().to_lowercase()) {
+                if directive == "no-store" {
+                    return false;
+                } else if directive == "no-cache" {
+                    self.max_age = 0;
+                    break;
+                } else if let Some(age) = directive.strip_prefix("max-age=") {
+                    self.max_age = age.trim_matches('"').parse().unwrap_or(0);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Update the entry with a "304 Not Modified" response
+    fn revalidate(&mut self, resp: &Response) {
+        self.stored = now();
+        self.set_max_age(resp);
+
+        if let Some(etag) = header(resp, headers::ETAG) {
+            self.etag = Some(etag);
+        }
+        if let Some(last_modified) = header(resp, headers::LAST_MODIFIED) {
+            self.last_modified = Some(last_modified);
+        }
+        if let Some(url) = location::response_url(resp) {
+            self.url = Some(url.to_string());
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        now().saturating_sub(self.stored) < self.max_age
+    }
+
+    /// Build a response from the cached body
+    async fn response(&self, path: &Path) -> io::Result<Response> {
+        let bytes = async_std::fs::read(path.with_extension("body")).await?;
+        let mut resp = http::Response::new(StatusCode::Ok);
+
+        if let Some(content_type) = &self.content_type {
+            resp.insert_header(headers::CONTENT_TYPE, content_type.as_str());
+        }
+        if let Some(etag) = &self.etag {
+            resp.insert_header(headers::ETAG, etag.as_str());
+        }
+        if let Some(last_modified) = &self.last_modified {
+            resp.insert_header(headers::LAST_MODIFIED, last_modified.as_str());
+        }
+        resp.set_body(bytes);
+
+        let mut resp = Response::from(resp);
+        if let Some(Ok(url)) = self.url.as_deref().map(Url::parse) {
+            resp.insert_ext(ResponseUrl(url));
+        }
+
+        Ok(resp)
+    }
+
+    async fn load(path: &Path) -> Option<Entry> {
+        let text = async_std::fs::read_to_string(path.with_extension("meta")).await.ok()?;
+        let mut entry = Entry::default();
+
+        for line in text.lines() {
+            if let Some((key, value)) = line.split_once(' ') {
+                match key {
+                    "stored" => entry.stored = value.parse().ok()?,
+                    "max-age" => entry.max_age = value.parse().ok()?,
+                    "etag" => entry.etag = Some(value.to_string()),
+                    "last-modified" => entry.last_modified = Some(value.to_string()),
+                    "content-type" => entry.content_type = Some(value.to_string()),
+                    "url" => entry.url = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+        }
+
+        Some(entry)
+    }
+
+    /// Save the entry metadata and, if given, its body
+    async fn save(&self, path: &Path, body: Option<&[u8]>) -> io::Result<()> {
+        async_std::fs::create_dir_all(cache_dir()).await?;
+
+        if let Some(body) = body {
+            write_atomic(&path.with_extension("body"), body).await?;
+        }
+
+        let mut text = format!("stored {}\nmax-age {}\n", self.stored, self.max_age);
+        for (key, value) in [
+            ("etag", &self.etag),
+            ("last-modified", &self.last_modified),
+            ("content-type", &self.content_type),
+            ("url", &self.url)
+        ] {
+            if let Some(value) = value {
+                text.push_str(&format!("{} {}\n", key, value));
+            }
+        }
+
+        write_atomic(&path.with_extension("meta"), text.as_bytes()).await
+    }
+}
+
+/// Write a file through a temporary one, so readers never see it half written
+async fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    let temp = path.with_extension(format!("{}.{}.tmp", extension, COUNTER.fetch_add(1, Ordering::Relaxed)));
+
+    async_std::fs::write(&temp, contents).await?;
+    if let Err(err) = async_std::fs::rename(&temp, path).await {
+        let _ = async_std::fs::remove_file(&temp).await;
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+fn header(resp: &Response, name: http::headers::HeaderName) -> Option<String> {
+    resp.header(name).map(|value| value.last().as_str().to_string())
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// 64-bit FNV-1a hash, stable across builds unlike the std hashers
+fn hash(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Path of an entry without extension
+fn entry_path(url: &Url) -> PathBuf {
+    cache_dir().join(format!("{:016x}", hash(url.as_str())))
+}
+
+fn cache_dir() -> PathBuf {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir);
+
+    base.join("share-preview").join("http")
+}
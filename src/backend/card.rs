@@ -12,20 +12,28 @@ use gettextrs::gettext;
 use crate::vec_of_strings;
 use crate::i18n::gettext_f;
 use super::{
+    AnimationFrame,
     Data,
     Image,
     ImageError,
     Log,
     LogLevel,
     Social,
+    rdfa,
     social::{
         SocialMetaLookup,
         SocialConstraints,
         SocialImageSizeKind
-    }
+    },
+    text
 };
 
-#[derive(Debug, Default, Clone)]
+/// Minimum length of a body paragraph to be used as a description
+const MIN_PARAGRAPH_LENGTH: usize = 50;
+/// Card types supported by Twitter
+const TWITTER_CARD_TYPES: [&str; 4] = ["summary", "summary_large_image", "app", "player"];
+
+#[derive(Debug, Default, Clone, PartialEq)]
 pub enum CardSize {
     #[default]
     Small,
@@ -42,6 +50,14 @@ impl CardSize {
         }
     }
 
+    pub fn image_kind(&self) -> SocialImageSizeKind {
+        match self {
+            Self::Small => SocialImageSizeKind::Small,
+            Self::Medium => SocialImageSizeKind::Medium,
+            Self::Large => SocialImageSizeKind::Large
+        }
+    }
+
     pub fn image_size(&self) -> (u32, u32) {
         match self {
             Self::Small => (64, 64),
@@ -50,6 +66,15 @@ impl CardSize {
         }
     }
 
+    /// Get the region of an image kept when filling the card image size
+    ///
+    /// Returns the (x, y, width, height) of the centered crop.
+    ///
+    pub fn crop(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let (card_width, card_height) = self.image_size();
+        crop_to_ratio(card_width as f64 / card_height as f64, width, height)
+    }
+
     pub fn icon_size(&self) -> i32 {
         match self {
             Self::Small => 32,
@@ -57,6 +82,70 @@ impl CardSize {
             Self::Large => 64
         }
     }
+
+    /// Get a translated name of the size to display
+    pub fn label(&self) -> String {
+        match self {
+            Self::Small => gettext("Small"),
+            Self::Medium => gettext("Medium"),
+            Self::Large => gettext("Large")
+        }
+    }
+}
+
+/// Get the centered region of an image with the given aspect ratio
+///
+/// Returns the (x, y, width, height) of the crop.
+///
+pub fn crop_to_ratio(ratio: f64, width: u32, height: u32) -> (u32, u32, u32, u32) {
+    if width as f64 / height as f64 > ratio {
+        let crop_width = ((height as f64 * ratio).round() as u32).min(width);
+        ((width - crop_width) / 2, 0, crop_width, height)
+    } else {
+        let crop_height = ((width as f64 / ratio).round() as u32).min(height);
+        (0, (height - crop_height) / 2, width, crop_height)
+    }
+}
+
+/// Get the region of an image visible in every crop, crops are centered
+pub fn safe_zone(crops: &[(u32, u32, u32, u32)], width: u32, height: u32) -> (u32, u32, u32, u32) {
+    let (mut zone_width, mut zone_height) = (width, height);
+
+    for (_, _, crop_width, crop_height) in crops.iter() {
+        zone_width = zone_width.min(*crop_width);
+        zone_height = zone_height.min(*crop_height);
+    }
+
+    ((width - zone_width) / 2, (height - zone_height) / 2, zone_width, zone_height)
+}
+
+/// A playable media rendered as a player card
+#[derive(Debug, Clone)]
+pub struct CardPlayer {
+    pub url: String,
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// App store listing rendered by app cards
+#[derive(Debug, Clone)]
+pub struct CardApp {
+    pub name: Option<String>,
+    /// Display names of the stores where the app is available
+    pub stores: Vec<String>,
+}
+
+/// An image processed to be displayed in a card
+#[derive(Debug, Clone)]
+pub struct CardThumbnail {
+    pub bytes: Vec<u8>,
+    pub size: CardSize,
+    pub animation: Option<Vec<AnimationFrame>>,
+    /// Alternative text paired with the image
+    pub alt: Option<String>,
+    /// The original image
+    pub source: Image,
 }
 
 #[derive(Debug, Clone)]
@@ -64,9 +153,20 @@ pub struct Card {
     pub title: String,
     pub site: String,
     pub favicon: Option<Vec<u8>>,
+    /// Theme color of the site, e.g. "#3584e4"
+    pub accent_color: Option<String>,
     pub description: Option<String>,
     pub image: Option<Vec<u8>>,
+    /// Frames of the image if the platform plays its animation
+    pub animation: Option<Vec<AnimationFrame>>,
+    /// Alternative text of the image
+    pub image_alt: Option<String>,
+    /// The original image the card image was made from
+    pub image_source: Option<Image>,
     pub size: CardSize,
+    pub player: Option<CardPlayer>,
+    pub app: Option<CardApp>,
+    pub author: Option<String>,
     pub social: Social,
 }
 
@@ -80,16 +180,22 @@ impl Card {
         let mut favicon: Option<Vec<u8>> = Option::None;
         let mut size = CardSize::default(); // Default card size
         let mut image: Option<Vec<u8>> = Option::None;
+        let mut animation: Option<Vec<AnimationFrame>> = Option::None;
+        let mut image_alt: Option<String> = Option::None;
+        let mut image_source: Option<Image> = Option::None;
         let mut image_sizes: Vec<SocialImageSizeKind> = Vec::new();
+        let mut player: Option<CardPlayer> = Option::None;
+        let mut app: Option<CardApp> = Option::None;
+        let mut author: Option<String> = Option::None;
 
-        if let Some(fav) = &data.favicon {
-            match fav.fetch().await {
-                Ok(bytes) => {
-                    favicon = Some(bytes);
-                },
-                Err(_) => {}
-            };
+        if let Some(favicon_size) = constraints.favicon_size {
+            favicon = Card::favicon(&social, data, favicon_size, logger).await;
         }
+        let accent_color = Card::accent_color(data);
+
+        Card::check_base_url(&social, data, logger);
+        Card::check_prefixes(&social, data, &constraints, logger);
+        Card::check_duplicates(&social, data, &constraints, logger);
 
         // Prepare with already available data
         match social {
@@ -97,6 +203,12 @@ impl Card {
                 image_sizes.push(SocialImageSizeKind::Large);
                 image_sizes.push(SocialImageSizeKind::Medium);
                 site = site.to_uppercase();
+
+                // Facebook renders pages with a video or audio as a player
+                player = Card::og_player(&social, data, logger);
+                if let Some(_) = player {
+                    image_sizes.retain(|kind| kind == &SocialImageSizeKind::Large);
+                }
             },
             Social::LinkedIn => {
                 image_sizes.push(SocialImageSizeKind::Large);
@@ -106,15 +218,18 @@ impl Card {
                 if let Social::Mastodon = social {
                     image_sizes.push(SocialImageSizeKind::Large);
                     image_sizes.push(SocialImageSizeKind::Medium);
+
+                    author = Card::fediverse_creator(&social, data, logger);
                 } else {
                     image_sizes.push(SocialImageSizeKind::Small);
                 }
 
                 // Mastodon and Discourse use og:site_name
                 let look = vec_of_strings!["og:site_name"];
-                if let Some(val) = data.lookup_meta(&look, None::<&dyn Log>) {
+                if let Some(val) = data.lookup_meta_ordered(&look, constraints.meta_last_wins, constraints.rdfa_prefixes, None::<&dyn Log>) {
                     if !val.is_empty() {
-                        site = val.to_string();
+                        site = text::normalize(&val);
+                        Card::log_displayed(&social, &val, &site, logger);
                         logger.log(LogLevel::Info, format!("{}: {}",
                             &social,
                             gettext_f("Found \"{name}\".", &[("name", "og:site_name")]))
@@ -125,13 +240,23 @@ impl Card {
                             gettext_f("\"{name}\" is empty!", &[("name", "og:site_name")])
                         ));
                     }
+                } else if let Some(name) = data.manifest.as_ref().and_then(|manifest| manifest.display_name()) {
+                    site = text::normalize(name);
+                    Card::log_displayed(&social, name, &site, logger);
+                    logger.log(LogLevel::Info, format!("{}: {}",
+                        &social,
+                        gettext_f(
+                            "Unable to find \"{name}\". Falling back to the web app manifest name.",
+                            &[("name", "og:site_name")]
+                        )
+                    ));
                 }
             },
             Social::Twitter => {}
         }
 
         // Get first available value from meta-tags to lookup
-        let pre_title = data.lookup_meta(&lookups.title, Some(logger));
+        let pre_title = data.lookup_meta_ordered(&lookups.title, constraints.meta_last_wins, constraints.rdfa_prefixes, Some(logger));
         let title = match &pre_title {
             Some(title) => title.to_string(),
             None => {
@@ -145,24 +270,44 @@ impl Card {
                         title.to_string()
                     },
                     None => {
-                        logger.log(LogLevel::Warning, format!("{}: {}",
-                            &social,
-                            gettext("Unable to find the document title!. Falling back to site url.")
-                        ));
+                        // Facebook and LinkedIn read the microdata name of the page last
+                        let look = vec_of_strings!["itemprop:name"];
+                        let name = match social {
+                            Social::Facebook | Social::LinkedIn => data.lookup_meta(&look, Some(logger)),
+                            _ => None
+                        };
+
+                        match name {
+                            Some(name) => name,
+                            None => {
+                                logger.log(LogLevel::Warning, format!("{}: {}",
+                                    &social,
+                                    gettext("Unable to find the document title!. Falling back to site url.")
+                                ));
 
-                        site.to_string()
+                                site.to_string()
+                            }
+                        }
                     },
                 }
             }
         };
 
-        // TODO: Get description from HTML for Facebook
-        let description = data.lookup_meta(&lookups.description, Some(logger));
+        let raw_title = title;
+        let title = text::normalize(&raw_title);
+
+        let raw_description = data.lookup_meta_ordered(&lookups.description, constraints.meta_last_wins, constraints.rdfa_prefixes, Some(logger));
+        let mut description = raw_description.as_deref().map(text::normalize);
+
+        // Facebook takes the description from the page content when missing
+        if let (Social::Facebook, None) = (&social, &description) {
+            description = Card::body_description(&social, data, logger);
+        }
 
         match &description {
             Some(text) => {
                 if let Social::LinkedIn = &social {
-                    if &text.chars().count() < &100 {
+                    if text::grapheme_count(text) < 100 {
                         logger.log(LogLevel::Warning, format!("{}: {}",
                             &social,
                             gettext_f("The description should be at least \"{count}\" characters long.", &[("count", "100")])
@@ -180,7 +325,7 @@ impl Card {
             }
         }
 
-        let card_type = data.lookup_meta(&lookups.kind, Some(logger));
+        let card_type = data.lookup_meta_ordered(&lookups.kind, constraints.meta_last_wins, constraints.rdfa_prefixes, Some(logger));
         if let Social::Twitter = &social {
             match card_type {
                 Some(_) => {
@@ -188,8 +333,29 @@ impl Card {
                     // Change card size by the value of "twitter:card" meta-tag
                     let look = vec_of_strings!["twitter:card"];
                     if let Some(val) = data.lookup_meta(&look, None::<&dyn Log>) {
-                        if val == "summary_large_image" {
-                            image_sizes.push(SocialImageSizeKind::Large);
+                        match val.as_str() {
+                            "summary" => {},
+                            "summary_large_image" => {
+                                image_sizes.push(SocialImageSizeKind::Large);
+                            },
+                            "player" => {
+                                player = Card::twitter_player(&social, data, logger);
+                                image_sizes.push(SocialImageSizeKind::Large);
+                            },
+                            "app" => {
+                                app = Card::twitter_app(&social, data, logger);
+                            },
+                            _ => {
+                                // Twitter doesn't render unknown card types
+                                logger.log(LogLevel::Error, format!("{}: {}",
+                                    &social,
+                                    gettext_f(
+                                        "Unknown card type \"{name}\", valid types are \"{types}\".",
+                                        &[("name", &val), ("types", &TWITTER_CARD_TYPES.join("\", \""))]
+                                    )
+                                ));
+                                return Err(CardError::TwitterUnknownCard(val));
+                            }
                         }
 
                         logger.log(LogLevel::Info, format!("{}: {}",
@@ -206,6 +372,8 @@ impl Card {
                         ));
                     }
                     image_sizes.push(SocialImageSizeKind::Medium);
+
+                    Card::check_twitter_handles(&social, data, logger);
                 },
                 None => {
                     // Return error if no card type is found for Twitter
@@ -232,7 +400,7 @@ impl Card {
             Social::LinkedIn | Social::Facebook => Some(data.get_body_images(5)),
             _ => None
         };
-        let images = data.lookup_meta_images(&lookups.image);
+        let images = data.lookup_meta_images(&lookups.image, constraints.rdfa_prefixes);
 
         match Card::lookup_image(
             &social,
@@ -242,9 +410,14 @@ impl Card {
             &constraints,
             logger
         ).await {
-            Some((i, s)) => {
-                image = Some(i);
-                size = s;
+            Some(thumbnail) => {
+                Card::check_image_alt(&social, &thumbnail.alt, &constraints, logger);
+
+                image = Some(thumbnail.bytes);
+                size = thumbnail.size;
+                animation = thumbnail.animation;
+                image_alt = thumbnail.alt;
+                image_source = Some(thumbnail.source);
             },
             None => {
                 match &social {
@@ -280,7 +453,378 @@ impl Card {
             }
         }
 
-        Ok(Card {title, site, favicon, description, image, size, social})
+        // App cards display the app name as title
+        let (raw_title, title) = match app.as_ref().and_then(|app| app.name.clone()) {
+            Some(name) => (name.clone(), name),
+            None => (raw_title, title)
+        };
+
+        // Facebook hides the description of cards with long titles
+        if let (Social::Facebook, Some(_)) = (&social, &description) {
+            if text::grapheme_count(&title) > 65 {
+                logger.log(LogLevel::Info, format!("{}: {}",
+                    &social,
+                    gettext_f(
+                        "The description is hidden for titles longer than \"{count}\" characters.",
+                        &[("count", "65")]
+                    )
+                ));
+                description = None;
+            }
+        }
+
+        // Cut texts the way the social would display them
+        let limits = social.text_limits(&size);
+        let title = match text::truncate(&title, &limits.title) {
+            Some(cut) => {
+                logger.log(LogLevel::Info, format!("{}: {}",
+                    &social,
+                    gettext_f("The title will be truncated to \"{text}\".", &[("text", &cut)])
+                ));
+                cut
+            },
+            None => {
+                Card::log_displayed(&social, &raw_title, &title, logger);
+                title
+            }
+        };
+        let description = match description {
+            Some(full) => match text::truncate(&full, &limits.description) {
+                Some(cut) => {
+                    logger.log(LogLevel::Info, format!("{}: {}",
+                        &social,
+                        gettext_f("The description will be truncated to \"{text}\".", &[("text", &cut)])
+                    ));
+                    Some(cut)
+                },
+                None => {
+                    if let Some(raw) = &raw_description {
+                        Card::log_displayed(&social, raw, &full, logger);
+                    }
+                    Some(full)
+                }
+            },
+            None => None
+        };
+
+        Ok(Card {
+            title, site, favicon, accent_color, description, image, animation, image_alt, image_source,
+            size, player, app, author, social
+        })
+    }
+
+    /// Get the declared icon best fitting the platform favicon size
+    async fn favicon(social: &Social, data: &Data, size: u32, logger: &impl Log) -> Option<Vec<u8>> {
+        for icon in data.lookup_icons(size) {
+            match icon.image.favicon(size).await {
+                Ok(bytes) => {
+                    logger.log(LogLevel::Info, format!("{}: {}",
+                        social,
+                        gettext_f(
+                            "Using favicon \"{url}\" from \"{source}\".",
+                            &[("url", icon.image.url.as_str()), ("source", &icon.source)]
+                        )
+                    ));
+                    return Some(bytes);
+                },
+                Err(err) => {
+                    logger.log(LogLevel::Debug, format!("{}: {}",
+                        social,
+                        gettext_f(
+                            "Skipping favicon \"{url}\": {error}",
+                            &[("url", icon.image.url.as_str()), ("error", &err.to_string())]
+                        )
+                    ));
+                }
+            }
+        }
+
+        logger.log(LogLevel::Warning, format!("{}: {}",
+            social,
+            gettext("Unable to find a valid favicon.")
+        ));
+
+        None
+    }
+
+    /// Get the site theme color, falling back to the web app manifest one
+    fn accent_color(data: &Data) -> Option<String> {
+        let look = vec_of_strings!["theme-color"];
+        data.lookup_meta(&look, None::<&dyn Log>)
+            .or_else(|| data.manifest.as_ref().and_then(|manifest| manifest.theme_color.clone()))
+    }
+
+    /// Get the first video declared with og:video, or the first og:audio
+    fn og_player(social: &Social, data: &Data, logger: &impl Log) -> Option<CardPlayer> {
+        let (media, kind) = match data.opengraph.videos.first() {
+            Some(video) => (video, "video"),
+            None => (data.opengraph.audios.first()?, "audio")
+        };
+        let url = media.secure_url.as_ref().or(media.url.as_ref())?;
+
+        logger.log(LogLevel::Info, format!("{}: {}",
+            social,
+            match kind {
+                "video" => gettext_f("Found video \"{url}\".", &[("url", url)]),
+                _ => gettext_f("Found audio \"{url}\".", &[("url", url)])
+            }
+        ));
+
+        if !url.starts_with("https://") {
+            logger.log(LogLevel::Warning, format!("{}: {}",
+                social,
+                match kind {
+                    "video" => gettext_f(
+                        "Video \"{url}\" should be served over HTTPS. Consider providing a \"{name}\" meta property.",
+                        &[("url", url), ("name", "og:video:secure_url")]
+                    ),
+                    _ => gettext_f(
+                        "Audio \"{url}\" should be served over HTTPS. Consider providing a \"{name}\" meta property.",
+                        &[("url", url), ("name", "og:audio:secure_url")]
+                    )
+                }
+            ));
+        }
+
+        if let None = media.mime_type {
+            let name = format!("og:{}:type", kind);
+            logger.log(LogLevel::Warning, format!("{}: {}",
+                social,
+                gettext_f(
+                    "Unable to find \"{name}\". Consider providing a \"{name}\" meta property.",
+                    &[("name", &name)]
+                )
+            ));
+        }
+
+        Some(CardPlayer {
+            url: url.to_string(),
+            mime_type: media.mime_type.clone(),
+            width: media.width,
+            height: media.height
+        })
+    }
+
+    /// Get the player of a Twitter player card
+    fn twitter_player(social: &Social, data: &Data, logger: &impl Log) -> Option<CardPlayer> {
+        let player = data.twitter.players.first();
+        let url = player.and_then(|player| player.url.as_ref());
+
+        match (player, url) {
+            (Some(player), Some(url)) => {
+                if !url.starts_with("https://") {
+                    logger.log(LogLevel::Error, format!("{}: {}",
+                        social,
+                        gettext_f("Player \"{url}\" must be served over HTTPS.", &[("url", url)])
+                    ));
+                }
+
+                for (name, value) in [("twitter:player:width", player.width), ("twitter:player:height", player.height)] {
+                    if let None = value {
+                        logger.log(LogLevel::Warning, format!("{}: {}",
+                            social,
+                            gettext_f(
+                                "Unable to find \"{name}\". Consider providing a \"{name}\" meta property.",
+                                &[("name", name)]
+                            )
+                        ));
+                    }
+                }
+
+                Some(CardPlayer {
+                    url: url.to_string(),
+                    mime_type: None,
+                    width: player.width,
+                    height: player.height
+                })
+            },
+            _ => {
+                logger.log(LogLevel::Error, format!("{}: {}",
+                    social,
+                    gettext_f(
+                        "Unable to find \"{name}\". Consider providing a \"{name}\" meta property.",
+                        &[("name", "twitter:player")]
+                    )
+                ));
+                None
+            }
+        }
+    }
+
+    /// Get and validate the app stores listing of a Twitter app card
+    fn twitter_app(social: &Social, data: &Data, logger: &impl Log) -> Option<CardApp> {
+        let mut name: Option<String> = None;
+        let mut stores: Vec<String> = Vec::new();
+
+        for (store, store_name) in [("iphone", "App Store"), ("ipad", "App Store"), ("googleplay", "Google Play")] {
+            let listing = data.twitter.apps.iter().find(|app| app.store == store);
+            let property = format!("twitter:app:id:{}", store);
+
+            match listing.and_then(|app| app.id.as_ref()) {
+                Some(id) => {
+                    // App Store ids are numeric and Google Play ids are package names
+                    let valid = match store {
+                        "googleplay" => id.contains('.') && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_'),
+                        _ => !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())
+                    };
+
+                    if !valid {
+                        logger.log(LogLevel::Error, format!("{}: {}",
+                            social,
+                            gettext_f(
+                                "\"{name}\" value \"{value}\" is not a valid store id.",
+                                &[("name", &property), ("value", id)]
+                            )
+                        ));
+                    }
+
+                    if !stores.contains(&store_name.to_string()) {
+                        stores.push(store_name.to_string());
+                    }
+                },
+                None => {
+                    logger.log(LogLevel::Error, format!("{}: {}",
+                        social,
+                        gettext_f(
+                            "Unable to find \"{name}\". Consider providing a \"{name}\" meta property.",
+                            &[("name", &property)]
+                        )
+                    ));
+                }
+            }
+
+            if let (None, Some(app)) = (&name, listing) {
+                name = app.name.clone();
+            }
+        }
+
+        if let None = data.twitter.site {
+            logger.log(LogLevel::Error, format!("{}: {}",
+                social,
+                gettext_f(
+                    "Unable to find \"{name}\". Consider providing a \"{name}\" meta property.",
+                    &[("name", "twitter:site")]
+                )
+            ));
+        }
+
+        if stores.is_empty() {
+            return None;
+        }
+
+        Some(CardApp { name, stores })
+    }
+
+    /// Get and validate the author handle from fediverse:creator
+    fn fediverse_creator(social: &Social, data: &Data, logger: &impl Log) -> Option<String> {
+        let look = vec_of_strings!["fediverse:creator"];
+
+        match data.lookup_meta(&look, Some(logger)) {
+            Some(handle) => {
+                // Handles look like "@username@example.com"
+                let valid = match handle.strip_prefix('@').and_then(|handle| handle.split_once('@')) {
+                    Some((username, domain)) => {
+                        !username.is_empty()
+                            && username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                            && domain.contains('.')
+                            && domain.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '-')
+                    },
+                    None => false
+                };
+
+                if valid {
+                    logger.log(LogLevel::Info, format!("{}: {}",
+                        social,
+                        gettext_f("Found \"{name}\".", &[("name", "fediverse:creator")])
+                    ));
+                    Some(handle)
+                } else {
+                    logger.log(LogLevel::Warning, format!("{}: {}",
+                        social,
+                        gettext_f(
+                            "\"{name}\" value \"{value}\" is not a valid handle, it should look like \"@username@example.com\".",
+                            &[("name", "fediverse:creator"), ("value", &handle)]
+                        )
+                    ));
+                    None
+                }
+            },
+            None => {
+                if let Some("article") = data.opengraph.kind.as_deref() {
+                    logger.log(LogLevel::Info, format!("{}: {}",
+                        social,
+                        gettext_f(
+                            "Add a \"{tag}\" tag to the page to show the article author.",
+                            &[("tag", "fediverse:creator")]
+                        )
+                    ));
+                }
+                None
+            }
+        }
+    }
+
+    /// Validate the format of the twitter:site and twitter:creator handles
+    fn check_twitter_handles(social: &Social, data: &Data, logger: &impl Log) {
+        for (property, value) in [("twitter:site", &data.twitter.site), ("twitter:creator", &data.twitter.creator)] {
+            if let Some(handle) = value {
+                let valid = match handle.strip_prefix('@') {
+                    Some(username) => {
+                        !username.is_empty()
+                            && username.len() <= 15
+                            && username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    },
+                    None => false
+                };
+
+                if !valid {
+                    logger.log(LogLevel::Warning, format!("{}: {}",
+                        social,
+                        gettext_f(
+                            "\"{name}\" value \"{value}\" is not a valid handle, it should look like \"@username\".",
+                            &[("name", property), ("value", handle)]
+                        )
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Log if a text will be displayed differently than it is written
+    fn log_displayed(social: &Social, raw: &str, text: &str, logger: &impl Log) {
+        if raw != text {
+            logger.log(LogLevel::Info, format!("{}: {}",
+                social,
+                gettext_f(
+                    "\"{raw}\" will be displayed as \"{text}\".",
+                    &[("raw", raw), ("text", text)]
+                )
+            ));
+        }
+    }
+
+    /// Get the first meaningful paragraph of the page body
+    fn body_description(social: &Social, data: &Data, logger: &impl Log) -> Option<String> {
+        for (i, paragraph) in data.body_paragraphs.iter().enumerate() {
+            let paragraph = text::normalize(paragraph);
+
+            if text::grapheme_count(&paragraph) >= MIN_PARAGRAPH_LENGTH {
+                logger.log(LogLevel::Info, format!("{}: {}",
+                    social,
+                    gettext_f(
+                        "No description found, using paragraph {number} of the page body: \"{text}\".",
+                        &[("number", &(i + 1).to_string()), ("text", &paragraph)]
+                    )
+                ));
+                return Some(paragraph);
+            }
+        }
+
+        logger.log(LogLevel::Debug, format!("{}: {}",
+            social,
+            gettext("No meaningful paragraph found in the page body for the description.")
+        ));
+        None
     }
 
     pub async fn lookup_image(
@@ -290,24 +834,24 @@ impl Card {
         kinds: &Vec<SocialImageSizeKind>,
         constraints: &SocialConstraints,
         logger: &impl Log
-    ) -> Option<(Vec<u8>, CardSize)> {
+    ) -> Option<CardThumbnail> {
 
         let mut look_body = true;
         let result = Card::get_ideal_image(social, images, kinds, constraints, false, logger).await;
 
-        if let Some((_, _, ideal)) = result {
+        if let Some((_, ideal)) = result {
             look_body = !ideal;
         }
 
         if let (true, Some(body_images)) = (look_body, body_images) {
-            if let Some((bytes, size, ideal)) = Card::get_ideal_image(social, body_images, kinds, constraints, true, logger).await {
+            if let Some((thumbnail, ideal)) = Card::get_ideal_image(social, body_images, kinds, constraints, true, logger).await {
                 if ideal {
-                    return Some((bytes, size));
+                    return Some(thumbnail);
                 }
             }
         }
-        if let Some((bytes, size, _)) = result {
-            return Some((bytes, size));
+        if let Some((thumbnail, _)) = result {
+            return Some(thumbnail);
         }
 
         None
@@ -320,7 +864,7 @@ impl Card {
         constraints: &SocialConstraints,
         first_fit: bool,
         logger: &impl Log
-    ) -> Option<(Vec<u8>, CardSize, bool)> {
+    ) -> Option<(CardThumbnail, bool)> {
         let mut valid: HashMap<SocialImageSizeKind, Vec<&Image>> = HashMap::new();
         let mut first_valid: Option<(&&Image, &SocialImageSizeKind)> = None;
         let mut recommended: Option<(&&Image, &SocialImageSizeKind)> = None;
@@ -334,7 +878,26 @@ impl Card {
                 continue
             }
 
-            match image.check(&social, kinds, constraints).await {
+            let result = image.check(&social, kinds, constraints).await;
+
+            if image.declared_mismatch() {
+                let declared = format!(
+                    "{}×{}px",
+                    image.declared_width.get().map_or("?".to_string(), |w| w.to_string()),
+                    image.declared_height.get().map_or("?".to_string(), |h| h.to_string())
+                );
+                let (width, height) = image.size();
+                logger.log(LogLevel::Warning, gettext_f(
+                    "Image \"{url}\" declares dimensions of {declared} but is {actual}.",
+                    &[
+                        ("url", &image.url.as_str()),
+                        ("declared", &declared),
+                        ("actual", &format!("{}×{}px", width, height))
+                    ]
+                ));
+            }
+
+            match result {
                 Ok(kind) => {
                     logger.log(LogLevel::Debug, gettext_f(
                         "Image \"{url}\" met the requirements.", &[("url", &image.url.as_str())]
@@ -359,7 +922,8 @@ impl Card {
                                 "{}: \"{}\".", err, image.url
                             ));
                         },
-                        ImageError::TooHeavy{..} | ImageError::Unsupported(_) => {
+                        ImageError::TooHeavy{..} | ImageError::Oversized(_) | ImageError::UnsupportedFormat{..}
+                        | ImageError::Unpreviewable(_) => {
                             logger.log(LogLevel::Warning, format!("{}: {}",
                                 social,
                                 gettext_f(
@@ -413,12 +977,12 @@ impl Card {
         }
 
         if let Some((image, kind)) = recommended {
-            if let Some((bytes, size)) = Card::thumbnail_image(image, kind, logger).await {
-                return Some((bytes, size, true));
+            if let Some(thumbnail) = Card::thumbnail_image(social, image, kind, constraints, logger).await {
+                return Some((thumbnail, true));
             }
         } else if let Some((image, kind)) = first_valid {
-            if let Some((bytes, size)) = Card::thumbnail_image(image, kind, logger).await {
-                return Some((bytes, size, false));
+            if let Some(thumbnail) = Card::thumbnail_image(social, image, kind, constraints, logger).await {
+                return Some((thumbnail, false));
             }
         }
 
@@ -426,10 +990,12 @@ impl Card {
     }
 
     pub async fn thumbnail_image(
+        social: &Social,
         image: &&Image,
         kind: &SocialImageSizeKind,
+        constraints: &SocialConstraints,
         logger: &impl Log
-    ) -> Option<(Vec<u8>, CardSize)> {
+    ) -> Option<CardThumbnail> {
         let size = CardSize::from_social(kind);
         let (width, height) = size.image_size();
 
@@ -439,7 +1005,13 @@ impl Card {
                 logger.log(LogLevel::Debug, gettext_f(
                     "Image \"{url}\" processed successfully.", &[("url", &image.url.as_str())]
                 ));
-                return Some((bytes, size));
+
+                let animation = Card::animate_image(social, image, &size, constraints, logger).await;
+                let alt = image.alt.borrow().as_ref()
+                    .map(|alt| text::normalize(alt))
+                    .filter(|alt| !alt.is_empty());
+
+                return Some(CardThumbnail { bytes, size, animation, alt, source: (*image).clone() });
             },
             Err(err) => {
                 logger.log(LogLevel::Debug, gettext_f(
@@ -451,12 +1023,172 @@ impl Card {
 
         None
     }
+
+    /// Warn about relative URLs depending on how crawlers resolve them
+    ///
+    /// Some crawlers ignore <base href> or resolve against the URL before
+    /// redirects, so these URLs may point somewhere else for them.
+    ///
+    fn check_base_url(social: &Social, data: &Data, logger: &impl Log) {
+        for (src, url) in data.ambiguous_urls() {
+            logger.log(LogLevel::Warning, format!("{}: {}",
+                social,
+                gettext_f(
+                    "Relative URL \"{src}\" resolves to \"{url}\", but crawlers ignoring the document base or redirects resolve it differently. Consider using an absolute URL.",
+                    &[("src", src), ("url", url.as_str())]
+                )
+            ));
+        }
+    }
+
+    /// Warn about properties only recognized by expanding the page RDFa prefixes
+    fn check_prefixes(
+        social: &Social,
+        data: &Data,
+        constraints: &SocialConstraints,
+        logger: &impl Log
+    ) {
+        if constraints.rdfa_prefixes {
+            return;
+        }
+
+        for meta in data.metadata.iter() {
+            for (property, iri) in meta.property.iter().zip(meta.iris.iter()) {
+                // Properties with a default prefix after expanding, but spelled differently
+                let name = rdfa::compact(iri);
+                if name != *iri && name != *property {
+                    logger.log(LogLevel::Warning, format!("{}: {}",
+                        social,
+                        gettext_f(
+                            "\"{property}\" uses a prefix declared by the page, {social} only reads \"{name}\".",
+                            &[("property", property), ("social", &social.to_string()), ("name", &name)]
+                        )
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Warn about repeated tags with different contents and tell which one is used
+    fn check_duplicates(
+        social: &Social,
+        data: &Data,
+        constraints: &SocialConstraints,
+        logger: &impl Log
+    ) {
+        for duplicate in data.duplicates() {
+            let count = duplicate.values.len().to_string();
+            let value = duplicate.pick(constraints.meta_last_wins);
+
+            if !duplicate.is_conflicting() {
+                logger.log(LogLevel::Debug, format!("{}: {}",
+                    social,
+                    gettext_f(
+                        "\"{name}\" is repeated {count} times with the same value.",
+                        &[("name", &duplicate.key), ("count", &count)]
+                    )
+                ));
+            } else if constraints.meta_last_wins {
+                logger.log(LogLevel::Warning, format!("{}: {}",
+                    social,
+                    gettext_f(
+                        "Found {count} \"{name}\" tags with different values, the last one is used: \"{value}\".",
+                        &[("name", &duplicate.key), ("count", &count), ("value", value)]
+                    )
+                ));
+            } else {
+                logger.log(LogLevel::Warning, format!("{}: {}",
+                    social,
+                    gettext_f(
+                        "Found {count} \"{name}\" tags with different values, the first one is used: \"{value}\".",
+                        &[("name", &duplicate.key), ("count", &count), ("value", value)]
+                    )
+                ));
+            }
+        }
+    }
+
+    /// Warn about missing or too long alternative text for the card image
+    fn check_image_alt(
+        social: &Social,
+        alt: &Option<String>,
+        constraints: &SocialConstraints,
+        logger: &impl Log
+    ) {
+        match alt {
+            Some(alt) => {
+                if let Some(max) = constraints.image_alt_length {
+                    let count = text::grapheme_count(alt);
+                    if count > max {
+                        logger.log(LogLevel::Warning, format!("{}: {}",
+                            social,
+                            gettext_f(
+                                "The image alternative text is {count} characters long, max length is {max}.",
+                                &[("count", &count.to_string()), ("max", &max.to_string())]
+                            )
+                        ));
+                    }
+                }
+            },
+            None => {
+                let tag = match social {
+                    Social::Twitter => "twitter:image:alt",
+                    _ => "og:image:alt"
+                };
+                logger.log(LogLevel::Warning, format!("{}: {}",
+                    social,
+                    gettext_f(
+                        "The image has no alternative text, add a \"{tag}\" tag to describe it.",
+                        &[("tag", tag)]
+                    )
+                ));
+            }
+        }
+    }
+
+    /// Get the animation frames of an image if the platform plays it
+    async fn animate_image(
+        social: &Social,
+        image: &Image,
+        size: &CardSize,
+        constraints: &SocialConstraints,
+        logger: &impl Log
+    ) -> Option<Vec<AnimationFrame>> {
+        if !image.is_animated().await {
+            return None;
+        }
+
+        if !constraints.image_animated {
+            logger.log(LogLevel::Info, format!("{}: {}",
+                social,
+                gettext_f(
+                    "Image \"{url}\" is animated but only its first frame will be shown.",
+                    &[("url", &image.url.as_str())]
+                )
+            ));
+            return None;
+        }
+
+        let (width, height) = size.image_size();
+        match image.animation(width, height).await {
+            Ok(animation) if animation.len() > 1 => Some(animation),
+            Ok(_) => None,
+            Err(err) => {
+                logger.log(LogLevel::Debug, gettext_f(
+                    "Failed to thumbnail \"{url}\": {info}.",
+                    &[("url", &image.url.as_str()), ("info", &err.to_string())]
+                ));
+                None
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum CardError {
     NotEnoughData,
-    TwitterNoCardFound
+    TwitterNoCardFound,
+    TwitterUnknownCard(String),
 }
 
 impl Display for CardError {
@@ -464,6 +1196,7 @@ impl Display for CardError {
         match *self {
             CardError::NotEnoughData => write!(f, "NotEnoughData"),
             CardError::TwitterNoCardFound => write!(f, "TwitterNoCardFound"),
+            CardError::TwitterUnknownCard(ref kind) => write!(f, "TwitterUnknownCard: {}", kind),
         }
     }
 }
@@ -4,28 +4,203 @@
 use url::Url;
 
 use crate::i18n::gettext_f;
-use super::{Card, CardError, Image, Log, LogLevel, Social, scrape, Error};
+use super::{Card, CardError, Image, Log, LogLevel, Manifest, OpenGraph, Social, TwitterCard, scrape, Error};
+use super::image::size_fit;
+use super::rdfa;
+use super::microdata::{MicrodataItem, MicrodataProperty};
+
+/// Prefix of microdata property names in lookups, e.g. "itemprop:name"
+pub const ITEMPROP_PREFIX: &str = "itemprop:";
+/// Properties that can be repeated, like Open Graph arrays and their structured properties
+const ARRAY_PROPERTIES: [&str; 10] = [
+    "og:image", "og:video", "og:audio", "og:locale:alternate",
+    "article:author", "article:tag", "book:author", "book:tag", "video:actor", "video:tag"
+];
+
+/// A meta name or property found more than once
+#[derive(Debug, Clone)]
+pub struct MetaDuplicate {
+    /// The name or property, properties with the default prefixes
+    pub key: String,
+    /// Contents in document order, missing contents are empty
+    pub values: Vec<String>,
+}
+
+impl MetaDuplicate {
+    /// Check if the occurrences have different contents
+    pub fn is_conflicting(&self) -> bool {
+        self.values.iter().any(|value| *value != self.values[0])
+    }
+
+    /// Get the content picked by a platform
+    ///
+    /// * `last_wins` - The platform takes the last occurrence instead of the first
+    ///
+    pub fn pick(&self, last_wins: bool) -> &str {
+        let value = if last_wins { self.values.last() } else { self.values.first() };
+        value.map(|value| value.as_str()).unwrap_or_default()
+    }
+}
 
 #[derive(Debug, Default, Clone)]
 pub struct Meta {
     pub name: Option<String>,
+    /// Properties as written in the page
     pub property: Vec<String>,
+    /// Properties expanded to full IRIs with the page RDFa prefixes
+    pub iris: Vec<String>,
     pub content: Option<String>,
     pub image: Option<Image>,
 }
 
+impl Meta {
+    /// Get a display key joining the meta name and properties
+    pub fn key(&self) -> String {
+        let mut names: Vec<String> = Vec::new();
+
+        if let Some(val) = &self.name {
+            names.push(val.to_string());
+        }
+        names.extend(self.property.iter().cloned());
+
+        names.join(" ")
+    }
+
+    /// Get the properties written with the default prefixes, e.g. "og:title"
+    pub fn properties(&self) -> Vec<String> {
+        self.iris.iter().map(|iri| rdfa::compact(iri)).collect()
+    }
+
+    /// Get the name and properties, properties with the default prefixes
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.name.iter().cloned().collect();
+
+        for property in self.properties() {
+            if !names.contains(&property) {
+                names.push(property);
+            }
+        }
+
+        names
+    }
+
+    /// Check if the meta has a name or property
+    ///
+    /// * `name` - The name or property, e.g. "description" or "og:title"
+    /// * `prefixes` - Compare properties by their expanded IRI instead of as written
+    ///
+    pub fn matches(&self, name: &str, prefixes: bool) -> bool {
+        let property = if prefixes {
+            self.iris.contains(&rdfa::expand_default(name))
+        } else {
+            self.property.iter().any(|property| property == name)
+        };
+
+        property || self.name.as_deref() == Some(name)
+    }
+}
+
+/// An icon declared by the page, a candidate for the site favicon
+#[derive(Debug, Clone)]
+pub struct Icon {
+    pub image: Image,
+    /// Where the icon was declared: its link rel, "manifest" or "favicon.ico" for the default location
+    pub source: String,
+    /// Declared sizes, empty if unknown
+    pub sizes: Vec<(u32, u32)>,
+    /// The icon is declared to scale to any size
+    pub any_size: bool,
+    pub mime_type: Option<String>,
+}
+
+impl Icon {
+    /// Create an icon parsing its declared sizes, like "16x16 32x32" or "any"
+    pub fn new(image: Image, source: &str, sizes: Option<&str>, mime_type: Option<String>) -> Icon {
+        let mut icon = Icon {
+            image,
+            source: source.to_string(),
+            sizes: Vec::new(),
+            any_size: false,
+            mime_type: mime_type.filter(|mime| !mime.is_empty()),
+        };
+
+        for size in sizes.unwrap_or_default().split_whitespace().map(|s| s.to_lowercase()) {
+            if size == "any" {
+                icon.any_size = true;
+            } else if let Some((width, height)) = size.split_once('x') {
+                if let (Ok(width), Ok(height)) = (width.parse(), height.parse()) {
+                    icon.sizes.push((width, height));
+                }
+            }
+        }
+
+        icon
+    }
+
+    /// Get a display key joining the icon source, sizes and type
+    pub fn key(&self) -> String {
+        let mut parts: Vec<String> = vec![self.source.to_string()];
+
+        if self.any_size {
+            parts.push(String::from("any"));
+        }
+        parts.extend(self.sizes.iter().map(|(width, height)| format!("{}×{}", width, height)));
+        if let Some(mime_type) = &self.mime_type {
+            parts.push(mime_type.to_string());
+        }
+
+        parts.join(" ")
+    }
+
+    /// Check if the icon can be rendered, SVG and Safari mask icons can't
+    pub fn is_raster(&self) -> bool {
+        self.source != "mask-icon"
+            && self.mime_type.as_deref() != Some("image/svg+xml")
+            && !self.image.url.path().to_lowercase().ends_with(".svg")
+    }
+
+    /// Sort key of how well the icon fits a size, lower is better
+    ///
+    /// Icons of unknown size go after the ones at least as big as the size
+    /// and before the smaller ones.
+    ///
+    pub fn fit(&self, size: u32) -> (u8, u32) {
+        self.sizes.iter()
+            .map(|(width, height)| size_fit(*width.max(height), size))
+            .min()
+            .unwrap_or((1, 0))
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Data {
     pub url: String,
+    /// Full URL of the scraped page, after redirects
+    pub page_url: Option<Url>,
+    /// URL the page was requested with, before redirects
+    pub requested_url: Option<Url>,
+    /// Base of the page relative URLs, the page URL or the document <base href>
+    pub base_url: Option<Url>,
     pub title: Option<String>,
-    pub favicon: Option<Image>,
+    /// Icons declared by the page, in document order
+    pub icons: Vec<Icon>,
+    /// The web app manifest linked by the page
+    pub manifest: Option<Manifest>,
     pub metadata: Vec<Meta>,
+    pub opengraph: OpenGraph,
+    pub twitter: TwitterCard,
     pub body_images: Vec<Image>,
+    /// Text of the page body paragraphs, in document order
+    pub body_paragraphs: Vec<String>,
+    /// Microdata items, the properties outside of any item go first
+    pub microdata: Vec<MicrodataItem>,
+    /// Links with rel="me", used by sites like Mastodon to verify profiles
+    pub rel_me: Vec<String>,
 }
 
 impl Data {
-    pub async fn from_url(url: &Url) -> Result<Data, Error> {
-        scrape(url).await
+    pub async fn from_url(url: &Url, reload: bool) -> Result<Data, Error> {
+        scrape(url, reload).await
     }
 
     pub async fn get_card(&self, social: Social, logger: &impl Log) -> Result<Card, CardError> {
@@ -35,84 +210,114 @@ impl Data {
     /// Get a Metas matching a name or property
     ///
     /// * `name` - The name or a property of the meta to get
+    /// * `prefixes` - Compare properties by their full IRI, so properties using
+    ///   prefixes declared by the page also match
     ///
-    pub fn get_meta(&self, name: &str) -> Vec<&Meta> {
-        let mut result = Vec::new();
-
-        for meta in self.metadata.iter() {
-
-            if meta.property.contains(&name.to_string()) {
-                result.push(meta);
-                continue;
-            }
-
-            if let Some(s) = &meta.name {
-                if s == name {
-                    result.push(meta);
-                }
-            }
-        }
-
-        result
+    pub fn get_meta(&self, name: &str, prefixes: bool) -> Vec<&Meta> {
+        self.metadata.iter().filter(|meta| meta.matches(name, prefixes)).collect()
     }
 
     /// Similar to get_meta() but directly looks for images
     ///
     /// * `name` - The name or a property of the meta to get
+    /// * `prefixes` - Compare properties by their full IRI
     ///
-    pub fn get_meta_image(&self, name: &str) -> Vec<&Image> {
-        let mut result = Vec::new();
+    pub fn get_meta_image(&self, name: &str, prefixes: bool) -> Vec<&Image> {
+        self.get_meta(name, prefixes).into_iter().filter_map(|meta| meta.image.as_ref()).collect()
+    }
+
+    /// Get the meta names and properties found more than once
+    ///
+    /// Properties that can be repeated, like og:image, are not included.
+    ///
+    pub fn duplicates(&self) -> Vec<MetaDuplicate> {
+        let mut occurrences: Vec<MetaDuplicate> = Vec::new();
 
         for meta in self.metadata.iter() {
+            let content = meta.content.clone().unwrap_or_default();
 
-            if meta.property.contains(&name.to_string()) {
-                if let Some(image) = &meta.image {
-                    result.push(image);
+            for key in meta.names() {
+                if ARRAY_PROPERTIES.iter().any(|array| key.starts_with(array)) {
+                    continue;
                 }
-                continue;
-            }
 
-            if let Some(s) = &meta.name {
-                if s == name {
-                    if let Some(image) = &meta.image {
-                        result.push(image);
-                    }
+                match occurrences.iter_mut().find(|occurrence| occurrence.key == key) {
+                    Some(occurrence) => occurrence.values.push(content.clone()),
+                    None => occurrences.push(MetaDuplicate { key, values: vec![content.clone()] })
                 }
             }
         }
 
-        result
+        occurrences.retain(|occurrence| occurrence.values.len() > 1);
+        occurrences
+    }
+
+    /// Get the microdata properties with a name, in document order of their items
+    ///
+    /// Only top level items are searched, nested items describe something
+    /// else than the page, like the author of an article.
+    ///
+    /// * `name` - The itemprop name, e.g. "description"
+    ///
+    pub fn get_itemprop(&self, name: &str) -> Vec<&MicrodataProperty> {
+        self.microdata.iter()
+            .filter(|item| item.top_level)
+            .flat_map(|item| item.properties.iter())
+            .filter(|property| property.name == name)
+            .collect()
     }
 
     /// Look for the first matching meta from a strings vector
     ///
     /// This method logs the lookup process if logger is provider
     ///
-    /// * `lookup` - Meta names and properties to lookup
+    /// * `lookup` - Meta names and properties to lookup, or microdata properties prefixed with "itemprop:"
     /// * `logger` - Log object to log the lookup process
     ///
     pub fn lookup_meta(&self, lookup: &Vec<String>, logger: Option<&(impl Log + ?Sized)>) -> Option<String> {
+        self.lookup_meta_ordered(lookup, false, false, logger)
+    }
+
+    /// Similar to lookup_meta() but can take the last occurrence of each meta
+    ///
+    /// * `lookup` - Meta names and properties to lookup
+    /// * `last_wins` - Take the last occurrence instead of the first
+    /// * `prefixes` - Match properties using RDFa prefixes declared by the page
+    /// * `logger` - Log object to log the lookup process
+    ///
+    pub fn lookup_meta_ordered(
+        &self,
+        lookup: &Vec<String>,
+        last_wins: bool,
+        prefixes: bool,
+        logger: Option<&(impl Log + ?Sized)>
+    ) -> Option<String> {
         for name in lookup.iter() {
-            let occurrences = self.get_meta(name);
-
-            if let Some(meta) = occurrences.first() {
-                if let Some(val) = &meta.content {
-                    if !val.is_empty() {
-                        if let Some(log) = logger {
-                            log.log(LogLevel::Debug, gettext_f(
-                                "Found a valid occurrence for \"{name}\" with value \"{value}\".",
-                                &[("name", name), ("value", val)]
-                            ));
-                        }
-                        return Some(val.to_string());
-                    } {
-                        if let Some(log) = logger {
-                            log.log(LogLevel::Warning, gettext_f(
-                                "\"{name}\" is empty!", &[("name", name)]
-                            ));
-                        }
-                        continue;
+            let occurrence = match name.strip_prefix(ITEMPROP_PREFIX) {
+                Some(itemprop) => self.get_itemprop(itemprop).first().map(|property| Some(property.value.clone())),
+                None => {
+                    let occurrences = self.get_meta(name, prefixes);
+                    let meta = if last_wins { occurrences.last() } else { occurrences.first() };
+                    meta.map(|meta| meta.content.clone())
+                }
+            };
+
+            if let Some(Some(val)) = &occurrence {
+                if !val.is_empty() {
+                    if let Some(log) = logger {
+                        log.log(LogLevel::Debug, gettext_f(
+                            "Found a valid occurrence for \"{name}\" with value \"{value}\".",
+                            &[("name", name), ("value", val)]
+                        ));
+                    }
+                    return Some(val.to_string());
+                } {
+                    if let Some(log) = logger {
+                        log.log(LogLevel::Warning, gettext_f(
+                            "\"{name}\" is empty!", &[("name", name)]
+                        ));
                     }
+                    continue;
                 }
             };
 
@@ -130,19 +335,73 @@ impl Data {
     /// This method unlike lookup_meta does not log the process
     ///
     /// * `lookup` - Meta names and properties to lookup
+    /// * `prefixes` - Match properties using RDFa prefixes declared by the page
     ///
-    pub fn lookup_meta_images(&self, lookup: &Vec<String>) -> Vec<&Image> {
+    pub fn lookup_meta_images(&self, lookup: &Vec<String>, prefixes: bool) -> Vec<&Image> {
 
         let mut images: Vec<&Image> = Vec::new();
         for name in lookup.iter() {
-            let occurrences = self.get_meta_image(name);
-            images.extend(occurrences);
+            match name.strip_prefix(ITEMPROP_PREFIX) {
+                Some(itemprop) => {
+                    images.extend(self.get_itemprop(itemprop).into_iter().filter_map(|property| property.image.as_ref()));
+                },
+                None => images.extend(self.get_meta_image(name, prefixes))
+            }
         }
 
         images
     }
 
 
+    /// Get the icons that can be rendered as a favicon, the best fitting a size first
+    ///
+    /// The manifest icons are used as a fallback after the page icons.
+    ///
+    /// * `size` - The favicon size in pixels
+    ///
+    pub fn lookup_icons(&self, size: u32) -> Vec<&Icon> {
+        fn sorted(icons: &[Icon], size: u32) -> Vec<&Icon> {
+            let mut icons: Vec<&Icon> = icons.iter().filter(|icon| icon.is_raster()).collect();
+            // Stable sort, icons fitting the same keep the document order
+            icons.sort_by_key(|icon| icon.fit(size));
+            icons
+        }
+
+        let mut icons = sorted(&self.icons, size);
+        if let Some(manifest) = &self.manifest {
+            icons.extend(sorted(&manifest.icons, size));
+        }
+
+        icons
+    }
+
+    /// Get the relative image URLs that resolve differently ignoring <base href> or redirects
+    ///
+    /// Returns the URLs as written in the page with the URL they resolve to.
+    ///
+    pub fn ambiguous_urls(&self) -> Vec<(&str, &Url)> {
+        let mut result: Vec<(&str, &Url)> = Vec::new();
+
+        let meta_images = self.metadata.iter().filter_map(|meta| meta.image.as_ref());
+        let icons = self.icons.iter().map(|icon| &icon.image);
+        let bases: Vec<&Url> = self.page_url.iter().chain(self.requested_url.iter()).collect();
+
+        for image in meta_images.chain(self.body_images.iter()).chain(icons) {
+            if !image.was_relative || result.iter().any(|(src, _)| *src == image.src) {
+                continue;
+            }
+
+            let differs = bases.iter().any(|base| {
+                base.join(&image.src).map(|other| other != image.url).unwrap_or(false)
+            });
+            if differs {
+                result.push((&image.src, &image.url));
+            }
+        }
+
+        result
+    }
+
     /// Gets Data's body_images with a return type matching lookup_meta_images
     ///
     /// It also truncates the vector to a max to avoid long loads
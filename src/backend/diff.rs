@@ -0,0 +1,109 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gettextrs::gettext;
+
+use super::{Card, Data};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+/// A single difference between two values with the same key
+#[derive(Debug, Clone)]
+pub struct Diff {
+    pub key: String,
+    pub kind: DiffKind,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl Diff {
+    pub fn new(key: &str, old: Option<String>, new: Option<String>) -> Diff {
+        let kind = match (&old, &new) {
+            (Some(a), Some(b)) if a == b => DiffKind::Unchanged,
+            (Some(_), Some(_)) => DiffKind::Changed,
+            (Some(_), None) => DiffKind::Removed,
+            (None, Some(_)) => DiffKind::Added,
+            (None, None) => DiffKind::Unchanged,
+        };
+
+        Diff { key: key.to_string(), kind, old, new }
+    }
+
+    /// Diff the document title and meta tags of two Data
+    ///
+    /// Metas are matched by key, repeated keys are matched in order of appearance.
+    ///
+    pub fn metadata(old: &Data, new: &Data) -> Vec<Diff> {
+        let mut result = vec![Diff::new("title", old.title.clone(), new.title.clone())];
+
+        // Collect keys keeping the order they appear, old ones first
+        let mut keys: Vec<String> = Vec::new();
+        for meta in old.metadata.iter().chain(new.metadata.iter()) {
+            let key = meta.key();
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        for key in keys.iter() {
+            let old_values = Diff::meta_values(old, key);
+            let new_values = Diff::meta_values(new, key);
+
+            for i in 0..old_values.len().max(new_values.len()) {
+                result.push(Diff::new(
+                    key,
+                    old_values.get(i).cloned(),
+                    new_values.get(i).cloned()
+                ));
+            }
+        }
+
+        result
+    }
+
+    /// Diff the visible values of two cards of the same social
+    pub fn cards(old: &Card, new: &Card) -> Vec<Diff> {
+        let mut result = vec![
+            Diff::new(&gettext("Title"), Some(old.title.clone()), Some(new.title.clone())),
+            Diff::new(&gettext("Site"), Some(old.site.clone()), Some(new.site.clone())),
+            Diff::new(&gettext("Description"), old.description.clone(), new.description.clone()),
+            Diff::new(&gettext("Size"), Some(old.size.label()), Some(new.size.label())),
+            Diff::new(&gettext("Author"), old.author.clone(), new.author.clone()),
+            Diff::new(&gettext("Image Alt Text"), old.image_alt.clone(), new.image_alt.clone()),
+            Diff::new(&gettext("Accent Color"), old.accent_color.clone(), new.accent_color.clone()),
+            Diff::new(
+                &gettext("Player"),
+                old.player.as_ref().map(|player| player.url.clone()),
+                new.player.as_ref().map(|player| player.url.clone())
+            ),
+        ];
+
+        // Images are compared by their processed bytes, no value to display
+        let mut image = Diff::new(
+            &gettext("Image"),
+            old.image.as_ref().map(|_| String::new()),
+            new.image.as_ref().map(|_| String::new())
+        );
+        if let (Some(a), Some(b)) = (&old.image, &new.image) {
+            if a != b {
+                image.kind = DiffKind::Changed;
+            }
+        }
+        result.push(image);
+
+        result
+    }
+
+    fn meta_values(data: &Data, key: &str) -> Vec<String> {
+        data.metadata.iter()
+            .filter(|meta| meta.key() == key)
+            .map(|meta| meta.content.clone().unwrap_or_default())
+            .collect()
+    }
+}
@@ -3,6 +3,8 @@
 
 use std::{
     cell::{RefCell, Cell},
+    cmp::Reverse,
+    convert::TryInto,
     error::Error,
     fmt::{Display, Formatter, Result as FmtResult},
     io::Cursor,
@@ -11,28 +13,79 @@ use std::{
 use data_url::DataUrl;
 use gettextrs::gettext;
 use human_bytes::human_bytes;
-use image;
+use async_std::io::ReadExt;
+use image::{
+    self,
+    AnimationDecoder,
+    ImageDecoder,
+    codecs::{gif::GifDecoder, webp::WebPDecoder},
+};
+use surf::StatusCode;
 use url::{Url, ParseError};
 
 use crate::i18n::gettext_f;
 use super::{
     CLIENT,
+    probe,
     Social,
     SocialImageSizeKind,
     SocialConstraints
 };
 
+/// Max bytes downloaded for a single image, independent of the platforms limits
+const MAX_DOWNLOAD_SIZE: usize = 25e+6 as usize;
+/// Max width or height of an image to decode
+const MAX_DIMENSION: u32 = 16384;
+/// Max memory a decoder can allocate for an image
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+/// Max number of frames decoded from an animated image
+const MAX_FRAMES: usize = 500;
+/// Browsers play frames with a shorter delay at the default delay
+const MIN_FRAME_DELAY: u32 = 10;
+const DEFAULT_FRAME_DELAY: u32 = 100;
+
+/// Sort key of how well an image size fits a target size, lower is better
+///
+/// Sizes at least as big as the target go first, the closest first, then
+/// the smaller ones, the biggest first.
+///
+pub fn size_fit(size: u32, target: u32) -> (u8, u32) {
+    if size >= target {
+        (0, size - target)
+    } else {
+        (2, target - size)
+    }
+}
+
+/// A thumbnailed frame of an animated image
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationFrame {
+    /// PNG bytes of the frame
+    pub bytes: Vec<u8>,
+    /// Time the frame is displayed in milliseconds
+    pub delay: u32,
+}
 
 #[derive(Debug, Clone)]
 pub struct Image {
     pub base_url: Url,
+    /// URL as written in the page
+    pub src: String,
     pub url: Url,
     pub was_relative: bool,
     pub bytes: RefCell<Option<Vec<u8>>>,
     pub format: Cell<Option<image::ImageFormat>>,
+    /// The image is a SVG, which has no raster format
+    pub svg: Cell<bool>,
     pub width: Cell<Option<u32>>,
     pub height: Cell<Option<u32>>,
-    pub size: Cell<Option<usize>>
+    pub size: Cell<Option<usize>>,
+    /// Width declared by the metadata, e.g. og:image:width
+    pub declared_width: Cell<Option<u32>>,
+    /// Height declared by the metadata, e.g. og:image:height
+    pub declared_height: Cell<Option<u32>>,
+    /// Alternative text, e.g. og:image:alt or the alt attribute
+    pub alt: RefCell<Option<String>>,
 }
 
 impl Image {
@@ -61,13 +114,18 @@ impl Image {
         Ok(
             Image {
                 base_url: base_url.clone(),
+                src: url.to_string(),
                 url: image_url,
                 was_relative,
                 bytes: RefCell::new(Option::default()),
                 format: Cell::new(Option::default()),
+                svg: Cell::new(false),
                 width: Cell::new(Option::default()),
                 height: Cell::new(Option::default()),
-                size: Cell::new(Option::default())
+                size: Cell::new(Option::default()),
+                declared_width: Cell::new(Option::default()),
+                declared_height: Cell::new(Option::default()),
+                alt: RefCell::new(Option::default()),
             }
         )
     }
@@ -88,6 +146,13 @@ impl Image {
                         let data = DataUrl::process(self.url.as_str())?;
                         let (body, _fragment) = data.decode_to_vec()?;
 
+                        if body.len() > MAX_DOWNLOAD_SIZE {
+                            return Err(Image::too_many_bytes());
+                        }
+
+                        let mime_type = data.mime_type().to_string();
+                        self.detect_format(&body, Some(&mime_type))?;
+
                         if let None = self.size.get() {
                             self.size.set(Some(body.len()));
                         }
@@ -99,17 +164,10 @@ impl Image {
                         let mut resp = CLIENT.get(&self.url).await?;
 
                         if resp.status().is_success() {
-                            let bytes = resp.body_bytes().await?;
-                            let format = image::guess_format(&bytes)?;
-
-                            if let None = self.format.get() {
-                                self.format.set(Some(format));
-                            }
-                            if let None = self.size.get() {
-                                self.size.set(Some(bytes.len()));
-                            }
+                            let mime_type = resp.content_type().map(|mime| mime.essence().to_string());
+                            let bytes = Image::read_body(&mut resp).await?;
 
-                            self.bytes.replace(Some(bytes));
+                            self.store(bytes, mime_type.as_deref())?;
                             Ok(self.bytes.borrow().clone().unwrap())
                         } else {
                             Err(ImageError::RequestError(resp.status().canonical_reason()))
@@ -120,6 +178,157 @@ impl Image {
         }
     }
 
+    /// Read the image format, dimensions and file size without downloading it
+    ///
+    /// Only the image header is requested if the server supports range
+    /// requests, otherwise the whole image is downloaded and saved.
+    ///
+    pub async fn probe(&self) -> Result<(), ImageError> {
+        // Nothing to probe for already downloaded or embedded images
+        if self.width.get().is_some() || self.bytes.borrow().is_some() || self.url.scheme() == "data" {
+            return Ok(());
+        }
+
+        let range = format!("bytes=0-{}", probe::PROBE_SIZE - 1);
+        let mut resp = CLIENT.get(&self.url).header("Range", range).await?;
+        let mime_type = resp.content_type().map(|mime| mime.essence().to_string());
+
+        match resp.status() {
+            StatusCode::PartialContent => {
+                let head = Image::read_body(&mut resp).await?;
+
+                // Get the total size from a range like "bytes 0-65535/1048576"
+                let total: Option<usize> = resp.header("Content-Range").and_then(|range| {
+                    range.last().as_str().rsplit('/').next().and_then(|total| total.parse().ok())
+                });
+
+                // The whole image fit in the range
+                if let Some(total) = total {
+                    if total <= head.len() {
+                        return self.store(head, mime_type.as_deref());
+                    }
+                    self.size.set(Some(total));
+                }
+
+                if Image::is_svg(&head, mime_type.as_deref()) {
+                    self.svg.set(true);
+                } else if let Some((format, width, height)) = probe::dimensions(&head) {
+                    self.format.set(Some(format));
+                    self.width.set(Some(width));
+                    self.height.set(Some(height));
+                } else if Image::is_avif(&head) {
+                    self.format.set(Some(image::ImageFormat::Avif));
+                }
+
+                Ok(())
+            },
+            // The server ignored the range and sent the whole image
+            status if status.is_success() => {
+                let bytes = Image::read_body(&mut resp).await?;
+                self.store(bytes, mime_type.as_deref())
+            },
+            status => Err(ImageError::RequestError(status.canonical_reason()))
+        }
+    }
+
+    /// Read a response body, stopping once it goes over the max download size
+    async fn read_body(resp: &mut surf::Response) -> Result<Vec<u8>, ImageError> {
+        if let Some(len) = resp.len() {
+            if len > MAX_DOWNLOAD_SIZE {
+                return Err(Image::too_many_bytes());
+            }
+        }
+
+        let mut bytes: Vec<u8> = Vec::new();
+        resp.take_body()
+            .take(MAX_DOWNLOAD_SIZE as u64 + 1)
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|err| ImageError::FetchError(surf::Error::from(err)))?;
+
+        if bytes.len() > MAX_DOWNLOAD_SIZE {
+            return Err(Image::too_many_bytes());
+        }
+
+        Ok(bytes)
+    }
+
+    fn too_many_bytes() -> ImageError {
+        ImageError::Oversized(gettext_f(
+            "file is larger than {max}", &[("max", &human_bytes(MAX_DOWNLOAD_SIZE as f64))]
+        ))
+    }
+
+    /// Decode an image with memory and dimensions limits
+    fn decode(bytes: &[u8]) -> Result<image::DynamicImage, ImageError> {
+        let mut reader = image::ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(|err| ImageError::ImageError(image::error::ImageError::IoError(err)))?;
+        reader.limits(Image::decoder_limits());
+
+        Ok(reader.decode()?)
+    }
+
+    fn decoder_limits() -> image::Limits {
+        let mut limits = image::Limits::default();
+        limits.max_image_width = Some(MAX_DIMENSION);
+        limits.max_image_height = Some(MAX_DIMENSION);
+        limits.max_alloc = Some(MAX_DECODE_ALLOC);
+        limits
+    }
+
+    /// Save the downloaded bytes with their format and size
+    fn store(&self, bytes: Vec<u8>, mime_type: Option<&str>) -> Result<(), ImageError> {
+        self.detect_format(&bytes, mime_type)?;
+
+        if let None = self.size.get() {
+            self.size.set(Some(bytes.len()));
+        }
+
+        self.bytes.replace(Some(bytes));
+        Ok(())
+    }
+
+    /// Detect the image format from its bytes
+    ///
+    /// SVG and AVIF are detected before guessing the format, SVG has no
+    /// raster format and AVIF is recognized even if it can't be decoded.
+    ///
+    fn detect_format(&self, bytes: &[u8], mime_type: Option<&str>) -> Result<(), ImageError> {
+        if Image::is_svg(bytes, mime_type) {
+            self.svg.set(true);
+            return Ok(());
+        }
+
+        let format = if Image::is_avif(bytes) {
+            image::ImageFormat::Avif
+        } else {
+            image::guess_format(bytes)?
+        };
+
+        if let None = self.format.get() {
+            self.format.set(Some(format));
+        }
+
+        Ok(())
+    }
+
+    fn is_svg(bytes: &[u8], mime_type: Option<&str>) -> bool {
+        if let Some("image/svg+xml") = mime_type {
+            return true;
+        }
+
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]);
+        let head = head.trim_start_matches('\u{FEFF}').trim_start();
+
+        (head.starts_with("<?xml") || head.starts_with("<svg") || head.starts_with("<!"))
+            && head.contains("<svg")
+    }
+
+    fn is_avif(bytes: &[u8]) -> bool {
+        bytes.len() >= 12 && &bytes[4..8] == b"ftyp" && matches!(&bytes[8..12], b"avif" | b"avis")
+    }
+
     /// Checks if the images meet the constrains of any of the given kinds.
     pub async fn check(
         &self,
@@ -127,12 +336,42 @@ impl Image {
         kinds: &Vec<SocialImageSizeKind>,
         constraints: &SocialConstraints
     ) -> Result<SocialImageSizeKind, ImageError> {
-        let bytes = self.fetch().await?;
+        // Avoid downloading images whose declared dimensions are already too small
+        if let (Some(width), Some(height)) = (self.declared_width.get(), self.declared_height.get()) {
+            Image::match_kind(social, kinds, width, height)?;
+        }
+
+        self.probe().await?;
+
+        // Don't download images the header already tells can't be used
+        self.check_format(social, constraints)?;
+
+        // Download the whole image if its header wasn't enough
+        if self.size.get().is_none() || self.width.get().is_none() || self.height.get().is_none() {
+            self.fetch().await?;
+        }
+
+        // Avoid decoding images too big to be handled safely
+        if let (Some(width), Some(height)) = (self.width.get(), self.height.get()) {
+            if width > MAX_DIMENSION || height > MAX_DIMENSION {
+                return Err(ImageError::Oversized(gettext_f(
+                    "{actual} is larger than {max}",
+                    &[
+                        ("actual", &format!("{}×{}px", width, height)),
+                        ("max", &format!("{}×{}px", MAX_DIMENSION, MAX_DIMENSION))
+                    ]
+                )));
+            }
+        }
+
+        // Check if image meets the file format limitations before decoding it
+        self.check_format(social, constraints)?;
 
         // Calculate image dimensions if not available
         if let (None, None) = (self.width.get(), self.height.get()) {
+            let bytes = self.fetch().await?;
             let (width, height) = async_std::task::spawn_blocking( move || -> Result<(u32, u32), ImageError> {
-                let image = image::load_from_memory(&bytes)?;
+                let image = Image::decode(&bytes)?;
                 Ok((image.width(), image.height()))
             })
             .await?;
@@ -151,45 +390,73 @@ impl Image {
             }
         }
 
-        // Check if image meets the file format limitations
+        // Check if the image dimensions are allowed by some of the size kinds
+        if let (Some(width), Some(height)) = (self.width.get(), self.height.get()) {
+            Image::match_kind(social, kinds, width, height)
+        } else {
+            Err(ImageError::Unexpected)
+        }
+    }
+
+    /// Check if the image format is known and supported by the social
+    fn check_format(&self, social: &Social, constraints: &SocialConstraints) -> Result<(), ImageError> {
+        if self.svg.get() {
+            return Err(ImageError::UnsupportedFormat{
+                social: social.clone(),
+                format: String::from("SVG")
+            });
+        }
+
         if let Some(format) = self.format.get() {
             if !constraints.image_formats.contains(&format) {
-                return Err(ImageError::Unsupported(gettext("Format is unsupported")));
+                return Err(ImageError::UnsupportedFormat{
+                    social: social.clone(),
+                    format: format!("{:?}", format).to_uppercase()
+                });
             }
-        }
-
-        // Check if the image dimensions are allowed by some of the size kinds
-        if let (Some(width), Some(height)) = (self.width.get(), self.height.get()) {
 
-            let mut min_width: u32 = 0;
-            let mut min_height: u32 = 0;
+            // The platform takes AVIF but it can't be decoded here to render the card
+            if let image::ImageFormat::Avif = format {
+                return Err(ImageError::Unpreviewable(String::from("AVIF")));
+            }
+        }
 
-            // Loop image size kinds
-            for kind in kinds.iter() {
+        Ok(())
+    }
 
-                // Check if image match minimum dimensions for this kind
-                let img_constraints = &social.image_size(kind);
-                (min_width, min_height) = img_constraints.minimum;
-                if width >= min_width && height >= min_height {
-                    // Mastodon requieres the width of the image to be larger than its height for its extended preview
-                    if let (Social::Mastodon, &SocialImageSizeKind::Large) = (social, kind) {
-                        if height >= width {
-                            continue;
-                        }
+    /// Get the first size kind allowed by the given dimensions
+    fn match_kind(
+        social: &Social,
+        kinds: &Vec<SocialImageSizeKind>,
+        width: u32,
+        height: u32
+    ) -> Result<SocialImageSizeKind, ImageError> {
+        let mut min_width: u32 = 0;
+        let mut min_height: u32 = 0;
+
+        // Loop image size kinds
+        for kind in kinds.iter() {
+
+            // Check if image match minimum dimensions for this kind
+            let img_constraints = &social.image_size(kind);
+            (min_width, min_height) = img_constraints.minimum;
+            if width >= min_width && height >= min_height {
+                // Mastodon requieres the width of the image to be larger than its height for its extended preview
+                if let (Social::Mastodon, &SocialImageSizeKind::Large) = (social, kind) {
+                    if height >= width {
+                        continue;
                     }
-                    // Kind matches! return it
-                    return Ok(kind.to_owned());
                 }
+                // Kind matches! return it
+                return Ok(kind.to_owned());
             }
-
-            // No size kind matched, return too tiny error
-            Err(ImageError::TooTiny{
-                actual: format!("{}×{}px", width, height),
-                min: format!("{}×{}px", min_width, min_height)
-            })
-        } else {
-            Err(ImageError::Unexpected)
         }
+
+        // No size kind matched, return too tiny error
+        Err(ImageError::TooTiny{
+            actual: format!("{}×{}px", width, height),
+            min: format!("{}×{}px", min_width, min_height)
+        })
     }
 
     pub async fn thumbnail(
@@ -201,7 +468,7 @@ impl Image {
 
         let thumbnail_bytes = async_std::task::spawn_blocking( move || -> Result<Vec<u8>, ImageError> {
             let mut thumbnail_bytes: Vec<u8> = Vec::new();
-            let image = image::load_from_memory(&bytes)?;
+            let image = Image::decode(&bytes)?;
 
             // Create thumbnail
             let thumbnail = image.resize_to_fill(
@@ -219,6 +486,191 @@ impl Image {
         Ok(thumbnail_bytes)
     }
 
+    /// Get the image as a favicon of the given size
+    ///
+    /// ICO files can hold several images, the one best fitting the size is used.
+    ///
+    pub async fn favicon(&self, size: u32) -> Result<Vec<u8>, ImageError> {
+        let bytes = self.fetch().await?;
+        let format = self.format.get();
+
+        let favicon_bytes = async_std::task::spawn_blocking( move || -> Result<Vec<u8>, ImageError> {
+            let mut favicon_bytes: Vec<u8> = Vec::new();
+            let image = match format {
+                Some(image::ImageFormat::Ico) => match Image::ico_entry(&bytes, size) {
+                    Some(entry) => Image::decode(&entry)?,
+                    None => Image::decode(&bytes)?
+                },
+                _ => Image::decode(&bytes)?
+            };
+
+            // Keep the aspect ratio of non square icons
+            let favicon = image.resize(size, size, image::imageops::FilterType::Triangle);
+
+            favicon.write_to(&mut Cursor::new(&mut favicon_bytes), image::ImageFormat::Png)?;
+            Ok(favicon_bytes)
+        })
+        .await?;
+
+        Ok(favicon_bytes)
+    }
+
+    /// Extract the image of an ICO file best fitting a size
+    ///
+    /// The decoder always picks the biggest image, so the chosen one is
+    /// returned as a single image ICO. Images of the same size with more
+    /// colors are preferred.
+    ///
+    fn ico_entry(bytes: &[u8], size: u32) -> Option<Vec<u8>> {
+        const HEADER_SIZE: usize = 6;
+        const ENTRY_SIZE: usize = 16;
+
+        let read_u16 = |bytes: &[u8], at: usize| -> Option<u16> {
+            Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+        };
+        let read_u32 = |bytes: &[u8], at: usize| -> Option<u32> {
+            Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+        };
+
+        let count = read_u16(bytes, 4)? as usize;
+        let entry = (0..count)
+            .filter_map(|i| bytes.get(HEADER_SIZE + i * ENTRY_SIZE..HEADER_SIZE + (i + 1) * ENTRY_SIZE))
+            .min_by_key(|entry| {
+                // A zero width or height means 256 pixels
+                let width = if entry[0] == 0 { 256 } else { entry[0] as u32 };
+                let height = if entry[1] == 0 { 256 } else { entry[1] as u32 };
+                let bits = read_u16(entry, 6).unwrap_or(0);
+                (size_fit(width.max(height), size), Reverse(bits))
+            })?;
+
+        let length = read_u32(entry, 8)? as usize;
+        let offset = read_u32(entry, 12)? as usize;
+        let data = bytes.get(offset..offset.checked_add(length)?)?;
+
+        let mut ico: Vec<u8> = Vec::with_capacity(HEADER_SIZE + ENTRY_SIZE + data.len());
+        ico.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+        ico.extend_from_slice(&entry[..12]);
+        ico.extend_from_slice(&((HEADER_SIZE + ENTRY_SIZE) as u32).to_le_bytes());
+        ico.extend_from_slice(data);
+
+        Some(ico)
+    }
+
+    /// Get the full resolution image as PNG so GTK can handle any format
+    pub async fn png(&self) -> Result<Vec<u8>, ImageError> {
+        let bytes = self.fetch().await?;
+
+        let png_bytes = async_std::task::spawn_blocking( move || -> Result<Vec<u8>, ImageError> {
+            let mut png_bytes: Vec<u8> = Vec::new();
+            let image = Image::decode(&bytes)?;
+
+            image.write_to(&mut Cursor::new(&mut png_bytes), image::ImageFormat::Png)?;
+            Ok(png_bytes)
+        })
+        .await?;
+
+        Ok(png_bytes)
+    }
+
+    /// Check if the image has more than one frame
+    pub async fn is_animated(&self) -> bool {
+        let bytes = match self.fetch().await {
+            Ok(bytes) => bytes,
+            Err(_) => return false
+        };
+        let format = self.format.get();
+
+        async_std::task::spawn_blocking(move || {
+            match Image::decode_frames(&bytes, format) {
+                Ok(Some(frames)) => frames.take(2).filter(|frame| frame.is_ok()).count() > 1,
+                _ => false
+            }
+        })
+        .await
+    }
+
+    /// Thumbnail every frame of an animated image
+    ///
+    /// Returns an empty vector if the image is not animated.
+    ///
+    pub async fn animation(
+        &self,
+        width: u32,
+        height: u32
+    ) -> Result<Vec<AnimationFrame>, ImageError> {
+        let bytes = self.fetch().await?;
+        let format = self.format.get();
+
+        let animation = async_std::task::spawn_blocking( move || -> Result<Vec<AnimationFrame>, ImageError> {
+            let mut animation: Vec<AnimationFrame> = Vec::new();
+
+            if let Some(frames) = Image::decode_frames(&bytes, format)? {
+                for frame in frames.take(MAX_FRAMES) {
+                    let frame = frame?;
+
+                    let (numer, denom) = frame.delay().numer_denom_ms();
+                    let mut delay = numer / denom.max(1);
+                    if delay <= MIN_FRAME_DELAY {
+                        delay = DEFAULT_FRAME_DELAY;
+                    }
+
+                    let image = image::DynamicImage::ImageRgba8(frame.into_buffer());
+                    let thumbnail = image.resize_to_fill(
+                        width,
+                        height,
+                        image::imageops::FilterType::Triangle
+                    );
+
+                    let mut bytes: Vec<u8> = Vec::new();
+                    thumbnail.write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+                    animation.push(AnimationFrame { bytes, delay });
+                }
+            }
+
+            Ok(animation)
+        })
+        .await?;
+
+        Ok(animation)
+    }
+
+    /// Get the frames of formats that can be animated
+    fn decode_frames(
+        bytes: &[u8],
+        format: Option<image::ImageFormat>
+    ) -> Result<Option<image::Frames<'_>>, ImageError> {
+        match format {
+            Some(image::ImageFormat::Gif) => {
+                let mut decoder = GifDecoder::new(Cursor::new(bytes))?;
+                decoder.set_limits(Image::decoder_limits())?;
+                Ok(Some(decoder.into_frames()))
+            },
+            Some(image::ImageFormat::WebP) => {
+                let mut decoder = WebPDecoder::new(Cursor::new(bytes))?;
+                decoder.set_limits(Image::decoder_limits())?;
+                if decoder.has_animation() {
+                    Ok(Some(decoder.into_frames()))
+                } else {
+                    Ok(None)
+                }
+            },
+            _ => Ok(None)
+        }
+    }
+
+    /// Check if the declared dimensions disagree with the actual ones
+    ///
+    /// If check() was never called before, this will just return false
+    ///
+    pub fn declared_mismatch(&self) -> bool {
+        let differs = |declared: Option<u32>, actual: Option<u32>| {
+            matches!((declared, actual), (Some(a), Some(b)) if a != b)
+        };
+
+        differs(self.declared_width.get(), self.width.get())
+            || differs(self.declared_height.get(), self.height.get())
+    }
+
     /// Return the image size as a tuple
     ///
     /// If check() was never called before, this will just return (0, 0)
@@ -247,7 +699,12 @@ pub enum ImageError {
         actual: String,
         max: String
     },
-    Unsupported(String),
+    Oversized(String),
+    UnsupportedFormat{
+        social: Social,
+        format: String
+    },
+    Unpreviewable(String),
     Unexpected,
 }
 
@@ -276,8 +733,18 @@ impl Display for ImageError {
                     "Image is too heavy ({actual}), max size is {max}",
                     &[("actual", actual), ("max", max)]
                 )),
-            ImageError::Unsupported(ref s) =>
-                write!(f, "{}", gettext_f("Image is unsupported: {info}", &[("info", s)])),
+            ImageError::Oversized(ref s) =>
+                write!(f, "{}", gettext_f("Image is too large to be processed safely: {info}", &[("info", s)])),
+            ImageError::UnsupportedFormat{ref social, ref format} =>
+                write!(f, "{}", gettext_f(
+                    "{format} images are not supported by {social}",
+                    &[("format", format), ("social", &social.to_string())]
+                )),
+            ImageError::Unpreviewable(ref format) =>
+                write!(f, "{}", gettext_f(
+                    "{format} images are supported but can't be previewed",
+                    &[("format", format)]
+                )),
             ImageError::Unexpected =>
                 write!(f, "{}", gettext("Unexpected Error")),
         }
@@ -304,10 +771,76 @@ impl From<surf::Error> for ImageError {
 
 impl From<image::error::ImageError> for ImageError {
     fn from(err: image::error::ImageError) -> ImageError {
-        ImageError::ImageError(err)
+        match err {
+            image::error::ImageError::Limits(ref limit) => ImageError::Oversized(limit.to_string()),
+            _ => ImageError::ImageError(err)
+        }
     }
 }
 
 impl Error for ImageError {
     fn description(&self) -> &str { "" }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build an ICO file from (width byte, height byte, bits per pixel, data) entries
+    fn ico(entries: &[(u8, u8, u16, &[u8])]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, 1, 0];
+        bytes.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+
+        let mut offset = 6 + entries.len() * 16;
+        for (width, height, bits, data) in entries {
+            bytes.extend_from_slice(&[*width, *height, 0, 0, 1, 0]);
+            bytes.extend_from_slice(&bits.to_le_bytes());
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += data.len();
+        }
+        for (_, _, _, data) in entries {
+            bytes.extend_from_slice(data);
+        }
+
+        bytes
+    }
+
+    /// Data of the single image of an extracted ICO
+    fn entry_data(bytes: &[u8]) -> &[u8] {
+        assert_eq!(&bytes[4..6], &[1, 0]);
+        assert_eq!(&bytes[18..22], &22u32.to_le_bytes());
+        &bytes[22..]
+    }
+
+    #[test]
+    fn ico_zero_dimensions_mean_256() {
+        let bytes = ico(&[(32, 32, 32, b"small"), (0, 0, 32, b"large")]);
+
+        // A zero read as 0 pixels would make the 32px image the closest to 48px
+        let entry = Image::ico_entry(&bytes, 48).unwrap();
+        assert_eq!(entry_data(&entry), b"large");
+        assert_eq!(entry[6], 0);
+
+        let entry = Image::ico_entry(&bytes, 256).unwrap();
+        assert_eq!(entry_data(&entry), b"large");
+
+        let entry = Image::ico_entry(&bytes, 16).unwrap();
+        assert_eq!(entry_data(&entry), b"small");
+    }
+
+    #[test]
+    fn ico_prefers_more_colors() {
+        let bytes = ico(&[(64, 64, 8, b"few"), (64, 64, 32, b"many"), (64, 64, 24, b"some")]);
+        let entry = Image::ico_entry(&bytes, 64).unwrap();
+        assert_eq!(entry_data(&entry), b"many");
+    }
+
+    #[test]
+    fn ico_rejects_truncated_files() {
+        let bytes = ico(&[(0, 0, 32, b"large")]);
+        assert_eq!(Image::ico_entry(&bytes[..bytes.len() - 1], 256), None);
+        assert_eq!(Image::ico_entry(&bytes[..10], 256), None);
+        assert_eq!(Image::ico_entry(&[], 256), None);
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use surf::{
+    Client,
+    Request,
+    Response,
+    middleware::{Middleware, Next},
+};
+use url::Url;
+
+/// The URL a response was served from, after following redirects
+#[derive(Debug, Clone)]
+pub struct ResponseUrl(pub Url);
+
+/// Save the requested URL into the response extensions
+///
+/// This must go after the redirect middleware to get the final URL.
+///
+#[derive(Debug, Default)]
+pub struct TrackUrl;
+
+#[surf::utils::async_trait]
+impl Middleware for TrackUrl {
+    async fn handle(&self, req: Request, client: Client, next: Next<'_>) -> surf::Result<Response> {
+        let url = req.url().clone();
+        let mut resp = next.run(req, client).await?;
+
+        resp.insert_ext(ResponseUrl(url));
+        Ok(resp)
+    }
+}
+
+/// Get the URL a response was served from, if known
+pub fn response_url(resp: &Response) -> Option<&Url> {
+    resp.ext::<ResponseUrl>().map(|ResponseUrl(url)| url)
+}
@@ -0,0 +1,85 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde_json::Value;
+use url::Url;
+
+use super::{Error, Icon, Image, CLIENT};
+
+/// A web app manifest, linked with rel="manifest"
+#[derive(Debug, Default, Clone)]
+pub struct Manifest {
+    pub url: Option<Url>,
+    pub name: Option<String>,
+    pub short_name: Option<String>,
+    /// Color used by the OS for the app, e.g. "#3584e4"
+    pub theme_color: Option<String>,
+    pub icons: Vec<Icon>,
+}
+
+impl Manifest {
+    /// Fetch and parse a manifest
+    ///
+    /// * `url` - The manifest URL
+    /// * `reload` - Don't use the cached manifest
+    ///
+    pub async fn from_url(url: &Url, reload: bool) -> Result<Manifest, Error> {
+        let mut req = CLIENT.get(url);
+        if reload {
+            req = req.header("Cache-Control", "no-cache");
+        }
+        let mut resp = req.await?;
+
+        if resp.status().is_success() {
+            Manifest::parse(&resp.body_string().await?, url)
+        } else {
+            Err(Error::Unexpected(resp.status().to_string()))
+        }
+    }
+
+    /// Parse a manifest, its URLs are relative to the manifest URL
+    pub fn parse(text: &str, url: &Url) -> Result<Manifest, Error> {
+        let json: Value = serde_json::from_str(text)
+            .map_err(|err| Error::Unexpected(err.to_string()))?;
+        let mut manifest = Manifest {
+            url: Some(url.clone()),
+            name: Manifest::get_string(&json, "name"),
+            short_name: Manifest::get_string(&json, "short_name"),
+            theme_color: Manifest::get_string(&json, "theme_color"),
+            ..Manifest::default()
+        };
+
+        for icon in json["icons"].as_array().into_iter().flatten() {
+            // Monochrome icons are only meant to be used as masks
+            let purpose = icon["purpose"].as_str().unwrap_or("any");
+            if purpose.split_whitespace().all(|value| value == "monochrome") {
+                continue;
+            }
+
+            if let Some(src) = icon["src"].as_str() {
+                if let Ok(image) = Image::new(&src.trim().to_string(), url) {
+                    manifest.icons.push(Icon::new(
+                        image,
+                        "manifest",
+                        icon["sizes"].as_str(),
+                        icon["type"].as_str().map(|mime| mime.trim().to_lowercase())
+                    ));
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Get a non empty string member
+    fn get_string(json: &Value, member: &str) -> Option<String> {
+        json[member].as_str()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Get the name, falling back to the short name
+    pub fn display_name(&self) -> Option<&String> {
+        self.name.as_ref().or(self.short_name.as_ref())
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use scraper::{Html, Selector, element_ref::ElementRef};
+use url::Url;
+
+use super::Image;
+
+/// A property of a microdata item, declared with itemprop
+#[derive(Debug, Clone)]
+pub struct MicrodataProperty {
+    pub name: String,
+    pub value: String,
+    /// The value as an image, for "image" properties
+    pub image: Option<Image>,
+}
+
+/// A microdata item, declared with itemscope
+#[derive(Debug, Default, Clone)]
+pub struct MicrodataItem {
+    /// The item type, e.g. "https://schema.org/Article"
+    pub itemtype: Option<String>,
+    /// The item isn't the value of a property of another item
+    pub top_level: bool,
+    pub properties: Vec<MicrodataProperty>,
+}
+
+/// Extract the microdata items of a document
+///
+/// Properties outside of any item are grouped in a first item without type,
+/// pages often use them to describe the whole document.
+///
+/// * `document` - The parsed HTML document
+/// * `url` - The base of relative URL values
+///
+pub fn extract(document: &Html, url: &Url) -> Vec<MicrodataItem> {
+    let mut document_item = MicrodataItem { top_level: true, ..MicrodataItem::default() };
+    let mut scopes = Vec::new();
+    let mut items = Vec::new();
+
+    let selector = Selector::parse("[itemscope]").unwrap();
+    for element in document.select(&selector) {
+        scopes.push(element.id());
+        items.push(MicrodataItem {
+            itemtype: attr(&element, "itemtype"),
+            top_level: element.value().attr("itemprop").is_none(),
+            properties: Vec::new(),
+        });
+    }
+
+    let selector = Selector::parse("[itemprop]").unwrap();
+    for element in document.select(&selector) {
+        let value = property_value(&element, url);
+
+        // Properties belong to the closest item containing them
+        let scope = element.ancestors()
+            .filter_map(ElementRef::wrap)
+            .find(|ancestor| ancestor.value().attr("itemscope").is_some())
+            .and_then(|ancestor| scopes.iter().position(|id| *id == ancestor.id()));
+        let item = match scope {
+            Some(index) => &mut items[index],
+            None => &mut document_item
+        };
+
+        for name in attr(&element, "itemprop").unwrap_or_default().split_whitespace() {
+            let image = match name {
+                "image" | "thumbnailUrl" => Image::new(&value, url).ok(),
+                _ => None
+            };
+
+            item.properties.push(MicrodataProperty {
+                name: name.to_string(),
+                value: value.to_string(),
+                image,
+            });
+        }
+    }
+
+    if !document_item.properties.is_empty() {
+        items.insert(0, document_item);
+    }
+    items.retain(|item| !item.properties.is_empty());
+
+    items
+}
+
+/// Get a property value depending on the element, like the HTML spec defines
+fn property_value(element: &ElementRef, url: &Url) -> String {
+    // Nested items are described by their own properties
+    if element.value().attr("itemscope").is_some() {
+        return attr(element, "itemtype").unwrap_or_default();
+    }
+
+    let url_value = |name: &str| -> String {
+        let value = attr(element, name).unwrap_or_default();
+        match url.join(&value) {
+            Ok(absolute) => absolute.to_string(),
+            Err(_) => value
+        }
+    };
+
+    match element.value().name() {
+        "meta" => attr(element, "content").unwrap_or_default(),
+        "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => url_value("src"),
+        "a" | "area" | "link" => url_value("href"),
+        "object" => url_value("data"),
+        "data" | "meter" => attr(element, "value").unwrap_or_default(),
+        "time" => attr(element, "datetime").unwrap_or_else(|| text(element)),
+        _ => text(element)
+    }
+}
+
+fn attr(element: &ElementRef, name: &str) -> Option<String> {
+    element.value().attr(name).map(|value| value.trim().to_string())
+}
+
+fn text(element: &ElementRef) -> String {
+    element.text().collect::<Vec<&str>>().concat().split_whitespace().collect::<Vec<&str>>().join(" ")
+}
@@ -1,14 +1,30 @@
 use once_cell::sync::Lazy;
+pub mod cache;
 pub mod card;
 pub mod data;
+pub mod diff;
 pub mod image;
+pub mod location;
 pub mod log;
+pub mod manifest;
+pub mod microdata;
+pub mod opengraph;
+pub mod probe;
+pub mod rdfa;
 pub mod scraper;
 pub mod social;
+pub mod text;
+pub mod verification;
 
-// surf Client for backend requests
+// surf Client for backend requests, the cache goes first to store responses by their original URL
+// and the URL tracking last to get the URL after redirects
 pub static CLIENT: Lazy<surf::Client> =
-    Lazy::new(|| surf::Client::new().with(surf::middleware::Redirect::default()));
+    Lazy::new(|| {
+        surf::Client::new()
+            .with(cache::HttpCache)
+            .with(surf::middleware::Redirect::default())
+            .with(location::TrackUrl)
+    });
 
 #[macro_export]
 macro_rules! vec_of_strings {
@@ -17,9 +33,13 @@ macro_rules! vec_of_strings {
 
 pub use self::{
     card::{Card, CardError, CardSize},
-    data::{Meta, Data},
-    image::{Image, ImageError},
+    data::{Icon, Meta, MetaDuplicate, Data},
+    diff::{Diff, DiffKind},
+    image::{AnimationFrame, Image, ImageError},
     log::{Log, LogLevel},
-    scraper::{scrape, Error},
+    manifest::Manifest,
+    opengraph::{OpenGraph, TwitterCard},
+    scraper::{scrape, page_version, Error},
     social::{Social, SocialConstraints, SocialImageSizeKind},
+    verification::verify_profile,
 };
@@ -0,0 +1,210 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::Meta;
+
+/// A media object with its structured properties
+///
+/// Structured properties like `og:image:width` describe the most recent
+/// root property (`og:image`) preceding them, as defined by the OGP spec.
+///
+#[derive(Debug, Default, Clone)]
+pub struct Media {
+    /// Index of the root meta in Data.metadata
+    pub index: usize,
+    /// Root property name, e.g. "og:image"
+    pub property: String,
+    pub url: Option<String>,
+    pub secure_url: Option<String>,
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub alt: Option<String>,
+    /// Direct stream URL of a player, e.g. twitter:player:stream
+    pub stream: Option<String>,
+}
+
+impl Media {
+    fn new(index: usize, property: &str, url: Option<String>) -> Media {
+        Media {
+            index,
+            property: property.to_string(),
+            url,
+            ..Media::default()
+        }
+    }
+
+    /// Set a structured property value
+    ///
+    /// * `property` - The property name without the root prefix
+    /// * `value` - The property value
+    ///
+    fn set(&mut self, property: &str, value: &str) {
+        match property {
+            "secure_url" => self.secure_url = Some(value.to_string()),
+            "type" => self.mime_type = Some(value.to_string()),
+            "width" => self.width = value.parse().ok(),
+            "height" => self.height = value.parse().ok(),
+            "alt" => self.alt = Some(value.to_string()),
+            "stream" => self.stream = Some(value.to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// Typed Open Graph properties
+#[derive(Debug, Default, Clone)]
+pub struct OpenGraph {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub kind: Option<String>,
+    pub url: Option<String>,
+    pub site_name: Option<String>,
+    pub images: Vec<Media>,
+    pub videos: Vec<Media>,
+    pub audios: Vec<Media>,
+}
+
+impl OpenGraph {
+    pub fn new(metadata: &[Meta]) -> OpenGraph {
+        let mut og = OpenGraph::default();
+
+        for (index, meta) in metadata.iter().enumerate() {
+            for name in meta_names(meta) {
+                match name.as_str() {
+                    "og:title" => set_first(&mut og.title, &meta.content),
+                    "og:description" => set_first(&mut og.description, &meta.content),
+                    "og:type" => set_first(&mut og.kind, &meta.content),
+                    "og:url" => set_first(&mut og.url, &meta.content),
+                    "og:site_name" => set_first(&mut og.site_name, &meta.content),
+                    name => {
+                        group_media(&mut og.images, "og:image", index, name, &meta.content);
+                        group_media(&mut og.videos, "og:video", index, name, &meta.content);
+                        group_media(&mut og.audios, "og:audio", index, name, &meta.content);
+                    }
+                }
+            }
+        }
+
+        og
+    }
+}
+
+/// App store listing of a Twitter app card
+#[derive(Debug, Default, Clone)]
+pub struct TwitterApp {
+    /// Store name, one of "iphone", "ipad" or "googleplay"
+    pub store: String,
+    pub name: Option<String>,
+    pub id: Option<String>,
+    pub url: Option<String>,
+}
+
+/// Typed Twitter card properties
+#[derive(Debug, Default, Clone)]
+pub struct TwitterCard {
+    pub card: Option<String>,
+    pub site: Option<String>,
+    pub creator: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub images: Vec<Media>,
+    pub players: Vec<Media>,
+    pub apps: Vec<TwitterApp>,
+}
+
+impl TwitterCard {
+    pub fn new(metadata: &[Meta]) -> TwitterCard {
+        let mut twitter = TwitterCard::default();
+
+        for (index, meta) in metadata.iter().enumerate() {
+            for name in meta_names(meta) {
+                let name = name.as_str();
+                match name {
+                    "twitter:card" => set_first(&mut twitter.card, &meta.content),
+                    "twitter:site" => set_first(&mut twitter.site, &meta.content),
+                    "twitter:creator" => set_first(&mut twitter.creator, &meta.content),
+                    "twitter:title" => set_first(&mut twitter.title, &meta.content),
+                    "twitter:description" => set_first(&mut twitter.description, &meta.content),
+                    _ if name.starts_with("twitter:app:") => twitter.set_app(name, &meta.content),
+                    _ => {
+                        group_media(&mut twitter.images, "twitter:image", index, name, &meta.content);
+                        group_media(&mut twitter.players, "twitter:player", index, name, &meta.content);
+                    }
+                }
+            }
+        }
+
+        twitter
+    }
+
+    /// Set a "twitter:app:{field}:{store}" property
+    fn set_app(&mut self, name: &str, content: &Option<String>) {
+        let parts: Vec<&str> = name.split(':').collect();
+
+        if let (&[_, _, field, store], Some(value)) = (parts.as_slice(), content) {
+            let app = match self.apps.iter().position(|app| app.store == store) {
+                Some(position) => &mut self.apps[position],
+                None => {
+                    self.apps.push(TwitterApp { store: store.to_string(), ..TwitterApp::default() });
+                    self.apps.last_mut().unwrap()
+                }
+            };
+
+            let field = match field {
+                "name" => &mut app.name,
+                "id" => &mut app.id,
+                "url" => &mut app.url,
+                _ => return
+            };
+            field.get_or_insert_with(|| value.to_string());
+        }
+    }
+}
+
+/// Get the name and properties of a meta, properties with the default prefixes
+fn meta_names(meta: &Meta) -> Vec<String> {
+    let mut names: Vec<String> = meta.properties();
+
+    if let Some(name) = &meta.name {
+        names.push(name.to_string());
+    }
+
+    names
+}
+
+fn set_first(field: &mut Option<String>, content: &Option<String>) {
+    if let (None, Some(value)) = (&field, content) {
+        *field = Some(value.to_string());
+    }
+}
+
+/// Start a new media for a root property or group a structured property with the last one
+///
+/// URL aliases like `og:image:url` describe the last media, they only start
+/// a new one if there is none before them.
+///
+fn group_media(media: &mut Vec<Media>, root: &str, index: usize, name: &str, content: &Option<String>) {
+    if let Some(structured) = name.strip_prefix(root) {
+        match structured {
+            "" => {
+                media.push(Media::new(index, root, content.clone()));
+            },
+            ":url" | ":src" => {
+                match media.last_mut() {
+                    Some(last) => {
+                        if last.url.is_none() {
+                            last.url = content.clone();
+                        }
+                    },
+                    None => media.push(Media::new(index, root, content.clone()))
+                }
+            },
+            _ => {
+                if let (Some(property), Some(last), Some(value)) = (structured.strip_prefix(':'), media.last_mut(), content) {
+                    last.set(property, value);
+                }
+            }
+        }
+    }
+}
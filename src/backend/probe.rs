@@ -0,0 +1,237 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::TryInto;
+
+use image::ImageFormat;
+
+/// Bytes requested to read an image header
+///
+/// JPEG headers may come after big EXIF segments, so this is not just a few bytes.
+///
+pub const PROBE_SIZE: usize = 64 * 1024;
+
+/// Read the format and dimensions of an image from its header
+///
+/// Supports PNG, JPEG, GIF and WebP. Returns None if the header is
+/// incomplete or the format is not supported.
+///
+/// * `bytes` - The first bytes of the image
+///
+pub fn dimensions(bytes: &[u8]) -> Option<(ImageFormat, u32, u32)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        png(bytes)
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        gif(bytes)
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(&b"WEBP"[..]) {
+        webp(bytes)
+    } else if bytes.starts_with(b"\xFF\xD8") {
+        jpeg(bytes)
+    } else {
+        None
+    }
+}
+
+/// Width and height are in the IHDR chunk, always the first one
+fn png(bytes: &[u8]) -> Option<(ImageFormat, u32, u32)> {
+    if bytes.get(12..16)? != b"IHDR" {
+        return None;
+    }
+
+    Some((ImageFormat::Png, read_u32_be(bytes, 16)?, read_u32_be(bytes, 20)?))
+}
+
+/// Width and height are in the logical screen descriptor
+fn gif(bytes: &[u8]) -> Option<(ImageFormat, u32, u32)> {
+    Some((ImageFormat::Gif, read_u16_le(bytes, 6)? as u32, read_u16_le(bytes, 8)? as u32))
+}
+
+/// Width and height depend on the first chunk type
+fn webp(bytes: &[u8]) -> Option<(ImageFormat, u32, u32)> {
+    match bytes.get(12..16)? {
+        // Lossy, dimensions follow the key frame start code
+        b"VP8 " => {
+            if bytes.get(23..26)? != b"\x9D\x01\x2A" {
+                return None;
+            }
+            let width = read_u16_le(bytes, 26)? & 0x3FFF;
+            let height = read_u16_le(bytes, 28)? & 0x3FFF;
+            Some((ImageFormat::WebP, width as u32, height as u32))
+        },
+        // Lossless, dimensions minus one packed in 14 bits each
+        b"VP8L" => {
+            if *bytes.get(20)? != 0x2F {
+                return None;
+            }
+            let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+            Some((ImageFormat::WebP, (bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        },
+        // Extended, canvas dimensions minus one in 24 bits each
+        b"VP8X" => {
+            let width = read_u24_le(bytes, 24)? + 1;
+            let height = read_u24_le(bytes, 27)? + 1;
+            Some((ImageFormat::WebP, width, height))
+        },
+        _ => None
+    }
+}
+
+/// Width and height are in the start of frame segment
+fn jpeg(bytes: &[u8]) -> Option<(ImageFormat, u32, u32)> {
+    let mut i = 2;
+
+    loop {
+        // Skip fill bytes before the marker
+        if *bytes.get(i)? != 0xFF {
+            return None;
+        }
+        while *bytes.get(i)? == 0xFF {
+            i += 1;
+        }
+
+        let marker = *bytes.get(i)?;
+        match marker {
+            // Markers without a segment
+            0x01 | 0xD0..=0xD8 => {
+                i += 1;
+                continue;
+            },
+            // End of image or start of scan, no frame found
+            0xD9 | 0xDA => return None,
+            // Start of frame, excluding DHT, JPG and DAC
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height = read_u16_be(bytes, i + 4)?;
+                let width = read_u16_be(bytes, i + 6)?;
+                return Some((ImageFormat::Jpeg, width as u32, height as u32));
+            },
+            _ => {
+                let length = read_u16_be(bytes, i + 1)?;
+                i += 1 + length as usize;
+            }
+        }
+    }
+}
+
+fn read_u16_be(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u16_le(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u24_le(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 3)?;
+    Some(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16)
+}
+
+fn read_u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0DIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes
+    }
+
+    fn webp(chunk: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"RIFF\x00\x00\x00\x00WEBP".to_vec();
+        bytes.extend_from_slice(chunk);
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn jpeg() -> Vec<u8> {
+        let mut bytes = b"\xFF\xD8".to_vec();
+        // APP0 segment before the frame
+        bytes.extend_from_slice(b"\xFF\xE0\x00\x10JFIF\x00\x01\x01\x00\x00\x01\x00\x01\x00\x00");
+        // SOF0 with 8 bits precision, 600 height and 800 width
+        bytes.extend_from_slice(b"\xFF\xC0\x00\x11\x08\x02\x58\x03\x20\x03");
+        bytes
+    }
+
+    /// Every header cut short of its dimensions is rejected
+    fn assert_truncated_fails(bytes: &[u8], needed: usize) {
+        for len in 0..needed {
+            assert_eq!(dimensions(&bytes[..len]), None, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn reads_png() {
+        let bytes = png(1200, 630);
+        assert_eq!(dimensions(&bytes), Some((ImageFormat::Png, 1200, 630)));
+        assert_truncated_fails(&bytes, bytes.len());
+    }
+
+    #[test]
+    fn rejects_png_without_ihdr() {
+        let mut bytes = png(1200, 630);
+        bytes[12..16].copy_from_slice(b"IDAT");
+        assert_eq!(dimensions(&bytes), None);
+    }
+
+    #[test]
+    fn reads_gif() {
+        let bytes = b"GIF89a\xB0\x04\x76\x02".to_vec();
+        assert_eq!(dimensions(&bytes), Some((ImageFormat::Gif, 1200, 630)));
+        assert_truncated_fails(&bytes, bytes.len());
+    }
+
+    #[test]
+    fn reads_webp_lossy() {
+        let bytes = webp(b"VP8 ", b"\x00\x00\x00\x9D\x01\x2A\xB0\x04\x76\x02");
+        assert_eq!(dimensions(&bytes), Some((ImageFormat::WebP, 1200, 630)));
+        assert_truncated_fails(&bytes, bytes.len());
+    }
+
+    #[test]
+    fn rejects_webp_lossy_without_start_code() {
+        let bytes = webp(b"VP8 ", b"\x00\x00\x00\x00\x00\x00\xB0\x04\x76\x02");
+        assert_eq!(dimensions(&bytes), None);
+    }
+
+    #[test]
+    fn reads_webp_lossless() {
+        let bits: u32 = (1200 - 1) | ((630 - 1) << 14);
+        let mut data = vec![0x2F];
+        data.extend_from_slice(&bits.to_le_bytes());
+        let bytes = webp(b"VP8L", &data);
+        assert_eq!(dimensions(&bytes), Some((ImageFormat::WebP, 1200, 630)));
+        assert_truncated_fails(&bytes, bytes.len());
+    }
+
+    #[test]
+    fn reads_webp_extended() {
+        let bytes = webp(b"VP8X", b"\x10\x00\x00\x00\xAF\x04\x00\x75\x02\x00");
+        assert_eq!(dimensions(&bytes), Some((ImageFormat::WebP, 1200, 630)));
+        assert_truncated_fails(&bytes, bytes.len());
+    }
+
+    #[test]
+    fn reads_jpeg_after_other_segments() {
+        let bytes = jpeg();
+        assert_eq!(dimensions(&bytes), Some((ImageFormat::Jpeg, 800, 600)));
+        // The component count after the width isn't needed
+        assert_truncated_fails(&bytes, bytes.len() - 1);
+    }
+
+    #[test]
+    fn rejects_jpeg_without_frame() {
+        let bytes = b"\xFF\xD8\xFF\xE0\x00\x04\x00\x00\xFF\xDA\x00\x08".to_vec();
+        assert_eq!(dimensions(&bytes), None);
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert_eq!(dimensions(b"<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>"), None);
+        assert_eq!(dimensions(b""), None);
+    }
+}
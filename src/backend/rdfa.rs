@@ -0,0 +1,159 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::HashMap;
+
+use scraper::{Html, Selector};
+
+/// Prefixes known without declaring them, from the Open Graph protocol
+const DEFAULT_PREFIXES: [(&str, &str); 8] = [
+    ("og", "http://ogp.me/ns#"),
+    ("fb", "http://ogp.me/ns/fb#"),
+    ("article", "http://ogp.me/ns/article#"),
+    ("book", "http://ogp.me/ns/book#"),
+    ("books", "http://ogp.me/ns/books#"),
+    ("music", "http://ogp.me/ns/music#"),
+    ("profile", "http://ogp.me/ns/profile#"),
+    ("video", "http://ogp.me/ns/video#"),
+];
+/// Other spellings of the Open Graph namespaces, with their canonical form
+const NAMESPACE_ALIASES: [(&str, &str); 3] = [
+    ("https://ogp.me/ns", "http://ogp.me/ns"),
+    ("http://opengraphprotocol.org/schema/", "http://ogp.me/ns#"),
+    ("https://opengraphprotocol.org/schema/", "http://ogp.me/ns#"),
+];
+
+/// RDFa prefix mappings declared by a document
+#[derive(Debug, Default, Clone)]
+pub struct Prefixes(HashMap<String, String>);
+
+impl Prefixes {
+    /// Read the prefixes declared in the <html> and <head> elements
+    ///
+    /// Both the RDFa prefix attribute and the older xmlns attributes are read.
+    ///
+    pub fn from_document(document: &Html) -> Prefixes {
+        let mut prefixes = Prefixes::default();
+        let selector = Selector::parse("html, head").unwrap();
+
+        for element in document.select(&selector) {
+            for (name, value) in element.value().attrs() {
+                if name == "prefix" {
+                    prefixes.parse(value);
+                } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+                    prefixes.0.insert(prefix.to_lowercase(), value.trim().to_string());
+                }
+            }
+        }
+
+        prefixes
+    }
+
+    /// Parse a prefix attribute like "ogp: http://ogp.me/ns# fb: http://ogp.me/ns/fb#"
+    fn parse(&mut self, value: &str) {
+        let mut tokens = value.split_whitespace();
+
+        while let Some(token) = tokens.next() {
+            if let Some(prefix) = token.strip_suffix(':') {
+                if let Some(iri) = tokens.next() {
+                    self.0.insert(prefix.to_lowercase(), iri.to_string());
+                }
+            }
+        }
+    }
+
+    /// Expand a property to a full IRI, e.g. "ogp:title" to "http://ogp.me/ns#title"
+    ///
+    /// Declared prefixes take precedence over the default ones, known aliases
+    /// of the Open Graph namespaces are expanded to the canonical IRI.
+    /// Properties without a known prefix are returned as they are.
+    ///
+    pub fn expand(&self, property: &str) -> String {
+        if let Some((prefix, reference)) = property.split_once(':') {
+            if !reference.starts_with("//") {
+                if let Some(iri) = self.0.get(&prefix.to_lowercase()) {
+                    return canonical(&format!("{}{}", iri, reference));
+                }
+            }
+        }
+
+        expand_default(property)
+    }
+}
+
+/// Replace a known alias of an Open Graph namespace in an IRI
+fn canonical(iri: &str) -> String {
+    for (alias, namespace) in NAMESPACE_ALIASES.iter() {
+        if let Some(reference) = iri.strip_prefix(alias) {
+            return format!("{}{}", namespace, reference);
+        }
+    }
+
+    iri.to_string()
+}
+
+/// Expand a property using only the default prefixes
+pub fn expand_default(property: &str) -> String {
+    if let Some((prefix, reference)) = property.split_once(':') {
+        if let Some((_, iri)) = DEFAULT_PREFIXES.iter().find(|(name, _)| *name == prefix) {
+            return format!("{}{}", iri, reference);
+        }
+    }
+
+    property.to_string()
+}
+
+/// Compact a full IRI with the default prefixes, e.g. "http://ogp.me/ns#title" to "og:title"
+pub fn compact(iri: &str) -> String {
+    for (prefix, namespace) in DEFAULT_PREFIXES.iter() {
+        if let Some(reference) = iri.strip_prefix(namespace) {
+            return format!("{}:{}", prefix, reference);
+        }
+    }
+
+    iri.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefixes(html: &str) -> Prefixes {
+        Prefixes::from_document(&Html::parse_document(html))
+    }
+
+    #[test]
+    fn expands_default_prefixes() {
+        let prefixes = prefixes("<html><head></head></html>");
+        assert_eq!(prefixes.expand("og:title"), "http://ogp.me/ns#title");
+        assert_eq!(prefixes.expand("article:author"), "http://ogp.me/ns/article#author");
+        assert_eq!(prefixes.expand("description"), "description");
+    }
+
+    #[test]
+    fn expands_declared_prefixes() {
+        let prefixes = prefixes(r#"<html prefix="ogp: http://ogp.me/ns#"><head></head></html>"#);
+        assert_eq!(compact(&prefixes.expand("ogp:title")), "og:title");
+    }
+
+    #[test]
+    fn expands_https_namespace() {
+        let prefixes = prefixes(r#"<html prefix="og: https://ogp.me/ns# fb: https://ogp.me/ns/fb#"></html>"#);
+        assert_eq!(prefixes.expand("og:title"), expand_default("og:title"));
+        assert_eq!(compact(&prefixes.expand("fb:app_id")), "fb:app_id");
+    }
+
+    #[test]
+    fn expands_legacy_xmlns_namespace() {
+        let prefixes = prefixes(r#"<html xmlns:og="http://opengraphprotocol.org/schema/"></html>"#);
+        assert_eq!(prefixes.expand("og:image"), expand_default("og:image"));
+        assert_eq!(compact(&prefixes.expand("og:image")), "og:image");
+    }
+
+    #[test]
+    fn keeps_unknown_namespaces() {
+        let prefixes = prefixes(r#"<html prefix="og: http://example.com/ns#"></html>"#);
+        assert_eq!(prefixes.expand("og:title"), "http://example.com/ns#title");
+        assert_eq!(compact(&prefixes.expand("og:title")), "http://example.com/ns#title");
+    }
+}
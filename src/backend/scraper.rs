@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use std::{
+    cmp::Reverse,
     error,
     fmt::{Display, Formatter, Result as FmtResult}
 };
@@ -9,26 +10,58 @@ use std::{
 use url::Url;
 use scraper::{Html, Selector, element_ref::ElementRef};
 
-use super::{Data, Meta, Image, CLIENT};
+use super::{cache, location, microdata, rdfa::{self, Prefixes}, Data, Icon, Manifest, Meta, Image, OpenGraph, TwitterCard, CLIENT};
 
-const IMAGE_TAGS: [&str; 3] = ["og:image", "twitter:image", "twitter:image:src"];
+const IMAGE_TAGS: [&str; 4] = ["og:image", "og:image:url", "twitter:image", "twitter:image:src"];
+/// Link relations declaring icons, "shortcut" is ignored like browsers do
+const ICON_RELS: [&str; 4] = ["icon", "apple-touch-icon", "apple-touch-icon-precomposed", "mask-icon"];
+/// Attributes holding the real source of lazy loaded images, the src being a placeholder
+const LAZY_SRC_ATTRS: [&str; 2] = ["data-lazy-src", "data-src"];
+const LAZY_SRCSET_ATTRS: [&str; 2] = ["data-lazy-srcset", "data-srcset"];
+/// Elements whose paragraphs aren't part of the page content
+const BOILERPLATE_TAGS: [&str; 6] = ["nav", "header", "footer", "aside", "form", "noscript"];
 
-pub async fn scrape(url: &Url) -> Result<Data, Error> {
+pub async fn scrape(url: &Url, reload: bool) -> Result<Data, Error> {
     //! Request URL html body and scrape it to get the needed data
+    //! If reload is true, cached responses for the page and its images are not used
 
-    let mut resp = CLIENT.get(&url).await?;
+    let mut req = CLIENT.get(&url);
+    if reload {
+        req = req.header("Cache-Control", "no-cache");
+    }
+    let mut resp = req.await?;
 
     if resp.status().is_success() {
         let mut data = Data::default();
 
-        // Store favicon urls
-        let mut html_icons: Vec<String> = Vec::default();
+        // Relative URLs are resolved against the URL after redirects
+        let page_url = location::response_url(&resp).unwrap_or(url).clone();
+
+        // Store manifest url
+        let mut manifest: Option<Url> = None;
 
         // Call function to get data from html:
-        get_html_data(&resp.body_string().await?, &mut data, &url, &mut html_icons).await; // Write html data to a Vec<>
+        get_html_data(&resp.body_string().await?, &mut data, &page_url, &mut manifest).await; // Write html data to a Vec<>
+
+        data.manifest = get_manifest(manifest, reload).await;
+        add_default_favicon(&mut data, &page_url);
+
+        // Forget the cached images so they are downloaded again
+        if reload {
+            let meta_images = data.metadata.iter().filter_map(|meta| meta.image.as_ref());
+            let microdata_images = data.microdata.iter()
+                .flat_map(|item| item.properties.iter())
+                .filter_map(|property| property.image.as_ref());
+            let manifest_icons = data.manifest.iter().flat_map(|manifest| manifest.icons.iter());
+            let icons = data.icons.iter().chain(manifest_icons).map(|icon| &icon.image);
+            for image in meta_images.chain(microdata_images).chain(data.body_images.iter()).chain(icons) {
+                cache::remove(&image.url).await;
+            }
+        }
 
-        data.favicon = get_favicon(&url, html_icons).await;  // Set data favicon
-        data.url = url.host_str().unwrap().to_string(); // Set Data URL
+        data.url = page_url.host_str().unwrap().to_string(); // Set Data URL
+        data.page_url = Some(page_url);
+        data.requested_url = Some(url.clone());
 
         Ok(data)
     } else {
@@ -36,23 +69,54 @@ pub async fn scrape(url: &Url) -> Result<Data, Error> {
     }
 }
 
+pub async fn page_version(url: &Url) -> Result<Option<String>, Error> {
+    //! Request URL headers and get its ETag or Last-Modified validator
+
+    let resp = CLIENT.head(url).await?;
+
+    if resp.status().is_success() {
+        for name in ["ETag", "Last-Modified"] {
+            if let Some(value) = resp.header(name) {
+                return Ok(Some(value.last().as_str().to_string()));
+            }
+        }
+
+        Ok(None)
+    } else {
+        Err(Error::Unexpected(resp.status().to_string()))
+    }
+}
+
 async fn get_html_data(
         text: &String,
         data: &mut Data,
         url: &Url,
-        icons: &mut Vec<String>) {
+        manifest: &mut Option<Url>) {
     //! Parse html and get data
 
     let document = Html::parse_document(&text); // HTML document from request text
 
+    // Get the document base, <base href> can itself be relative to the page URL
+    let selector = Selector::parse("base[href]").unwrap();
+    let base = document.select(&selector).next()
+        .and_then(|element| get_attr_val(&element, "href"))
+        .and_then(|href| url.join(&href).ok())
+        .unwrap_or_else(|| url.clone());
+    data.base_url = Some(base.clone());
+
+    // Resolve every relative URL below against the document base
+    let url = &base;
+
     // Get document title
     let selector = Selector::parse("title").unwrap(); // HTML <title> selector
     // Try to get document title
     if let Some(title) = document.select(&selector).next() {
-        data.title = Some(title.inner_html().trim().to_string());
+        // The title content is plain text, entities are already decoded
+        data.title = Some(title.text().collect::<String>().trim().to_string());
     }
 
-    // Get meta tags
+    // Get meta tags, properties can use RDFa prefixes declared by the document
+    let prefixes = Prefixes::from_document(&document);
     let selector = Selector::parse("meta").unwrap();
     for element in document.select(&selector) {
         let name: Option<String> = get_attr_val(&element, "name");
@@ -61,8 +125,10 @@ async fn get_html_data(
             Some(value) => value.split(" ").map(|s| s.to_string()).collect(),
             None => Vec::new()
         };
+        let iris: Vec<String> = property.iter().map(|value| prefixes.expand(value)).collect();
+        let properties: Vec<String> = iris.iter().map(|iri| rdfa::compact(iri)).collect();
         let content: Option<String> = get_attr_val(&element, "content");
-        let image: Option<Image> = match (is_image(&name, &property), &content) {
+        let image: Option<Image> = match (is_image(&name, &properties), &content) {
             (true, Some(val)) => {
                 if let Ok(image) = Image::new(val, url) {
                     Some(image)
@@ -74,38 +140,91 @@ async fn get_html_data(
         };
 
         if let (Some(_), _) | (_, Some(_)) = (&name, property.last()) {
-            let meta = Meta {name, property, content, image };
+            let meta = Meta {name, property, iris, content, image };
             data.metadata.push(meta);
         }
     }
 
-    // Get icons
-    let selector = Selector::parse("link").unwrap();
+    // Group structured properties and apply declared dimensions and alt text to their images
+    data.opengraph = OpenGraph::new(&data.metadata);
+    data.twitter = TwitterCard::new(&data.metadata);
+    for media in data.opengraph.images.iter().chain(data.twitter.images.iter()) {
+        if let Some(image) = &data.metadata[media.index].image {
+            if let Some(width) = media.width {
+                image.declared_width.set(Some(width));
+            }
+            if let Some(height) = media.height {
+                image.declared_height.set(Some(height));
+            }
+            if let Some(alt) = &media.alt {
+                image.alt.replace(Some(alt.to_string()));
+            }
+        }
+    }
+
+    // Get icons and the manifest, rel can hold several values like "shortcut icon"
+    let selector = Selector::parse("link[rel][href]").unwrap();
+    for element in document.select(&selector) {
+        let rel: String = get_attr_val(&element, "rel").unwrap_or_default().to_lowercase();
+        let href: String = get_attr_val(&element, "href").unwrap_or_default();
+
+        if let Some(source) = rel.split_whitespace().find(|value| ICON_RELS.contains(value)) {
+            if let Ok(image) = Image::new(&href, url) {
+                data.icons.push(Icon::new(
+                    image,
+                    source,
+                    get_attr_val(&element, "sizes").as_deref(),
+                    get_attr_val(&element, "type").map(|mime| mime.to_lowercase())
+                ));
+            }
+        } else if rel.split_whitespace().any(|value| value == "manifest") && manifest.is_none() {
+            *manifest = url.join(&href).ok();
+        }
+    }
+
+    // Get microdata items
+    data.microdata = microdata::extract(&document, url);
+
+    // Get rel="me" links, used to verify profiles on other sites
+    let selector = Selector::parse("a[rel], link[rel]").unwrap();
     for element in document.select(&selector) {
-        let rel: Option<String> = get_attr_val(&element, "rel");
+        let rel: String = get_attr_val(&element, "rel").unwrap_or_default();
         let href: Option<String> = get_attr_val(&element, "href");
 
-        if let Some(value) = rel {
-            match value.as_str() {
-                "shortcut icon" | "icon" => {
-                    if let Some(url) = href {
-                        icons.push(url);
-                    }
-                },
-                _ => {},
+        if rel.split_whitespace().any(|value| value.eq_ignore_ascii_case("me")) {
+            if let Some(Ok(link)) = href.map(|href| url.join(&href)) {
+                if !data.rel_me.contains(&link.to_string()) {
+                    data.rel_me.push(link.to_string());
+                }
             }
         }
     }
 
-    // Get images
+    // Get body paragraphs, skipping the ones in navigation and page chrome
+    let selector = Selector::parse("body p").unwrap();
+    for element in document.select(&selector) {
+        let boilerplate = element.ancestors().any(|node| {
+            node.value().as_element()
+                .map(|parent| BOILERPLATE_TAGS.contains(&parent.name()))
+                .unwrap_or(false)
+        });
+
+        if !boilerplate {
+            let text = element.text().collect::<Vec<&str>>().concat();
+            let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+            if !text.is_empty() {
+                data.body_paragraphs.push(text);
+            }
+        }
+    }
+
+    // Get images, lazy loaded and responsive ones can declare several sources
     let selector = Selector::parse("img").unwrap();
     for element in document.select(&selector) {
-        if let Some(src) = element.value().attr("src") {
-            let src = src.trim().to_string();
-            if src.contains(".jpg") || src.contains(".jpeg") || src.contains(".png"){
-                if let Ok(image) = Image::new(&src, url) {
-                    data.body_images.push(image);
-                }
+        if let Some(image) = get_body_image(&element, url) {
+            if !data.body_images.iter().any(|other| other.url == image.url) {
+                image.alt.replace(get_attr_val(&element, "alt"));
+                data.body_images.push(image);
             }
         }
     }
@@ -131,26 +250,162 @@ fn is_image(name: &Option<String>, property: &Vec<String>) -> bool {
     false
 }
 
-async fn get_favicon(url: &Url, mut icons: Vec<String>) -> Option<Image> {
+/// A source of a body image, from its src or a srcset
+struct ImageSource {
+    url: String,
+    /// Width descriptor, e.g. "640w"
+    width: Option<u32>,
+    /// Pixel density descriptor, e.g. "2x"
+    density: Option<f32>,
+    mime_type: Option<String>,
+}
 
-    // Add standard favicon.ico to the list
-    if let Ok(favicon) = Url::parse(url.origin().unicode_serialization().as_str()) {
-        if let Ok(favicon) = favicon.join("favicon.ico") {
-            icons.push(favicon.to_string());
+fn get_body_image(element: &ElementRef, url: &Url) -> Option<Image> {
+    //! Pick the biggest source of an <img>, including its srcset and <picture> sources
+
+    let mut sources: Vec<ImageSource> = Vec::new();
+
+    // Lazy loading scripts replace the src with these attributes
+    let src = LAZY_SRC_ATTRS.iter()
+        .find_map(|attr| get_attr_val(element, attr))
+        .or_else(|| get_attr_val(element, "src"));
+    if let Some(src) = src {
+        sources.push(ImageSource { url: src, width: None, density: None, mime_type: None });
+    }
+
+    let srcset = LAZY_SRCSET_ATTRS.iter()
+        .find_map(|attr| get_attr_val(element, attr))
+        .or_else(|| get_attr_val(element, "srcset"));
+    if let Some(srcset) = srcset {
+        sources.extend(parse_srcset(&srcset, None));
+    }
+
+    // <source> elements of a <picture> go before its <img>
+    let picture = element.parent()
+        .and_then(ElementRef::wrap)
+        .filter(|parent| parent.value().name() == "picture");
+    if let Some(picture) = picture {
+        for source in picture.children().filter_map(ElementRef::wrap) {
+            if source.value().name() == "source" {
+                let srcset = LAZY_SRCSET_ATTRS.iter()
+                    .find_map(|attr| get_attr_val(&source, attr))
+                    .or_else(|| get_attr_val(&source, "srcset"));
+                if let Some(srcset) = srcset {
+                    sources.extend(parse_srcset(&srcset, get_attr_val(&source, "type")));
+                }
+            }
         }
     }
 
-    // Iterate favicon candidates
-    for icon in icons.iter() {
-        // Try to create an image
-        if let Ok(image) = Image::new(icon, url) {
-            if let Ok(_bytes) = image.fetch().await {
-                return Some(image)
+    // The element dimensions are its rendered size, a hint of the file one
+    let element_width: Option<u32> = get_attr_val(element, "width").and_then(|val| val.parse().ok());
+    let element_height: Option<u32> = get_attr_val(element, "height").and_then(|val| val.parse().ok());
+
+    // Width of each source to rank them, from its descriptors or the element dimensions
+    let source_width = |source: &ImageSource| -> Option<u32> {
+        match (source.width, source.density, element_width) {
+            (Some(width), _, _) => Some(width),
+            (None, Some(density), Some(width)) => Some((width as f32 * density).round() as u32),
+            (None, None, width) => width,
+            _ => None
+        }
+    };
+
+    // Data URLs are lazy loading placeholders and SVG can't be used by crawlers,
+    // AVIF is only used if there is nothing else since most platforms reject it
+    let (best, image) = sources.iter()
+        .filter(|source| !source.url.starts_with("data:"))
+        .filter_map(|source| Some((source, Image::new(&source.url, url).ok()?)))
+        .filter(|(_, image)| !image.url.path().to_lowercase().ends_with(".svg"))
+        .min_by_key(|(source, _)| (
+            source.mime_type.as_deref() == Some("image/avif"),
+            Reverse(source_width(source).unwrap_or(0))
+        ))?;
+
+    // Width descriptors take precedence over the element dimensions,
+    // keeping the element aspect ratio for the height
+    image.declared_width.set(source_width(best));
+    image.declared_height.set(match (best.width, element_width, element_height) {
+        (Some(width), Some(element_width), Some(element_height)) if element_width > 0 => {
+            Some((width as f64 * element_height as f64 / element_width as f64).round() as u32)
+        },
+        (None, _, Some(element_height)) => {
+            Some((element_height as f32 * best.density.unwrap_or(1.0)).round() as u32)
+        },
+        _ => None
+    });
+
+    Some(image)
+}
+
+fn parse_srcset(srcset: &str, mime_type: Option<String>) -> Vec<ImageSource> {
+    //! Parse a srcset value like "image.jpg 640w, image-2x.jpg 2x"
+    //! URLs can contain commas, so they are split on whitespace first
+
+    let mut sources: Vec<ImageSource> = Vec::new();
+    let mut rest = srcset;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() {
+            break;
+        }
+
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (url, after) = rest.split_at(end);
+
+        // A trailing comma ends a candidate without descriptors
+        let (url, descriptors, next) = if url.ends_with(',') {
+            (url.trim_end_matches(','), "", after)
+        } else {
+            let end = after.find(',').unwrap_or(after.len());
+            (url, &after[..end], &after[end..])
+        };
+
+        let mut source = ImageSource {
+            url: url.to_string(),
+            width: None,
+            density: None,
+            mime_type: mime_type.clone(),
+        };
+        for descriptor in descriptors.split_whitespace() {
+            if let Some(width) = descriptor.strip_suffix('w') {
+                source.width = width.parse().ok();
+            } else if let Some(density) = descriptor.strip_suffix('x') {
+                source.density = density.parse().ok();
             }
         }
+
+        sources.push(source);
+        rest = next;
     }
 
-    None
+    sources
+}
+
+async fn get_manifest(manifest: Option<Url>, reload: bool) -> Option<Manifest> {
+    //! Fetch and parse the web app manifest if linked
+
+    let manifest_url = manifest?;
+    match Manifest::from_url(&manifest_url, reload).await {
+        Ok(manifest) => Some(manifest),
+        Err(err) => {
+            log::debug!("Couldn't get manifest {}: {}", manifest_url, err);
+            None
+        }
+    }
+}
+
+fn add_default_favicon(data: &mut Data, url: &Url) {
+    //! Add the standard favicon.ico location, crawlers try it when no icon works
+
+    if let Ok(favicon) = url.join("/favicon.ico") {
+        if !data.icons.iter().any(|icon| icon.image.url == favicon) {
+            if let Ok(image) = Image::new(&favicon.to_string(), url) {
+                data.icons.push(Icon::new(image, "favicon.ico", None, None));
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -177,3 +432,64 @@ impl From<surf::Error> for Error {
 impl error::Error for Error {
     fn description(&self) -> &str { "" }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(srcset: &str) -> Vec<(String, Option<u32>, Option<f32>)> {
+        parse_srcset(srcset, None).into_iter()
+            .map(|source| (source.url, source.width, source.density))
+            .collect()
+    }
+
+    #[test]
+    fn parses_descriptors() {
+        assert_eq!(parse("small.jpg 640w, large.jpg 1280w"), vec![
+            ("small.jpg".to_string(), Some(640), None),
+            ("large.jpg".to_string(), Some(1280), None),
+        ]);
+        assert_eq!(parse("image.jpg, image-2x.jpg 2x, image-3x.jpg 2.5x"), vec![
+            ("image.jpg".to_string(), None, None),
+            ("image-2x.jpg".to_string(), None, Some(2.0)),
+            ("image-3x.jpg".to_string(), None, Some(2.5)),
+        ]);
+    }
+
+    #[test]
+    fn ignores_trailing_commas() {
+        assert_eq!(parse("a.jpg 1x, b.jpg 2x,"), vec![
+            ("a.jpg".to_string(), None, Some(1.0)),
+            ("b.jpg".to_string(), None, Some(2.0)),
+        ]);
+        assert_eq!(parse(" a.jpg,  ,b.jpg, "), vec![
+            ("a.jpg".to_string(), None, None),
+            ("b.jpg".to_string(), None, None),
+        ]);
+        assert_eq!(parse(", ,"), vec![]);
+    }
+
+    #[test]
+    fn keeps_commas_inside_urls() {
+        assert_eq!(parse("https://example.com/w_640,h_480/a.jpg 640w"), vec![
+            ("https://example.com/w_640,h_480/a.jpg".to_string(), Some(640), None),
+        ]);
+    }
+
+    #[test]
+    fn skips_malformed_descriptors() {
+        assert_eq!(parse("a.jpg -640w, b.jpg wide, c.jpg w, d.jpg 2xx, e.jpg 100h"), vec![
+            ("a.jpg".to_string(), None, None),
+            ("b.jpg".to_string(), None, None),
+            ("c.jpg".to_string(), None, None),
+            ("d.jpg".to_string(), None, None),
+            ("e.jpg".to_string(), None, None),
+        ]);
+    }
+
+    #[test]
+    fn keeps_source_type() {
+        let sources = parse_srcset("a.avif 1x", Some("image/avif".to_string()));
+        assert_eq!(sources[0].mime_type.as_deref(), Some("image/avif"));
+    }
+}
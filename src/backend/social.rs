@@ -9,6 +9,7 @@ use std::{
 use image::ImageFormat;
 
 use crate::vec_of_strings;
+use super::{CardSize, card::crop_to_ratio};
 
 const NAMES: [&str; 2] =  [
     "og:title", "title"
@@ -27,6 +28,10 @@ const IMAGE_FORMATS: [ImageFormat; 4] = [
     ImageFormat::WebP
 ];
 const MAX_SIZE: usize = 5e+6 as usize;
+/// Width of the rendered cards
+const CARD_WIDTH: i32 = 500;
+/// Padding around the cards text
+const CARD_PADDING: i32 = 12;
 
 /// Enumerates supported platforms
 #[derive(Debug, Clone, PartialEq)]
@@ -69,19 +74,37 @@ impl FromStr for Social {
 }
 
 impl Social {
+    /// All supported platforms, in the same order as the platform selector
+    pub fn all() -> [Social; 5] {
+        [
+            Self::Discourse,
+            Self::Facebook,
+            Self::LinkedIn,
+            Self::Mastodon,
+            Self::Twitter,
+        ]
+    }
+
+    /// Get the metadata looked up by the platform, in order
+    ///
+    /// Facebook and LinkedIn fall back to microdata properties, like Google does.
+    ///
     pub fn lookups(&self) -> SocialMetaLookup {
         SocialMetaLookup {
             title: match self {
                 Self::Twitter => vec_of_strings!["twitter:title", "og:title", "title"],
+                Self::Facebook | Self::LinkedIn => vec_of_strings!["og:title", "title"],
                 _ => NAMES.iter().map(|s| s.to_string()).collect::<Vec<String>>()
             },
             description: match self {
-                Self::LinkedIn => vec_of_strings!["og:description"],
+                Self::Facebook => vec_of_strings!["og:description", "description", "itemprop:description"],
+                Self::LinkedIn => vec_of_strings!["og:description", "itemprop:description"],
                 Self::Twitter => vec_of_strings!["twitter:description", "og:description"],
                 _ => DESCRIPTIONS.iter().map(|s| s.to_string()).collect::<Vec<String>>()
             },
             image: match self {
                 Self::Twitter => vec_of_strings!["twitter:image", "twitter:image:src", "og:image"],
+                Self::Facebook | Self::LinkedIn => vec_of_strings!["og:image", "itemprop:image"],
                 _ => IMAGES.iter().map(|s| s.to_string()).collect::<Vec<String>>()
             },
             kind: match self {
@@ -98,11 +121,99 @@ impl Social {
                 _ => MAX_SIZE
             },
             image_formats: match self {
+                // Mastodon and Discourse process images with libvips, which reads AVIF
+                Self::Discourse | Self::Mastodon => {
+                    let mut formats = IMAGE_FORMATS.to_vec();
+                    formats.push(ImageFormat::Avif);
+                    formats
+                },
+                Self::LinkedIn => vec![ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif],
                 _ => IMAGE_FORMATS.to_vec()
+            },
+            // Discourse embeds the original image, the others store a static copy
+            image_animated: matches!(self, Self::Discourse),
+            image_alt_length: match self {
+                Self::Twitter => Some(420),
+                Self::Mastodon => Some(1500),
+                _ => None
+            },
+            favicon_size: match self {
+                Self::Discourse => Some(16),
+                _ => None
+            },
+            // Facebook reads RDFa prefixes, the others only match the literal "og:" properties
+            rdfa_prefixes: matches!(self, Self::Facebook),
+            // Facebook and Discourse overwrite repeated tags while parsing, the others keep the first
+            meta_last_wins: matches!(self, Self::Discourse | Self::Facebook)
+        }
+    }
+
+    /// Get how the title and description are cut for the given card size
+    pub fn text_limits(&self, size: &CardSize) -> SocialTextLimits {
+        // Medium cards place the image next to the text
+        let width = match size {
+            CardSize::Medium => CARD_WIDTH - CARD_PADDING * 2 - size.image_size().0 as i32,
+            _ => CARD_WIDTH - CARD_PADDING * 2
+        };
+
+        match self {
+            Self::Discourse => SocialTextLimits {
+                title: TextLimit::None,
+                description: TextLimit::Chars(300)
+            },
+            Self::Facebook => SocialTextLimits {
+                title: TextLimit::Lines {
+                    lines: 2, width, font: "Helvetica, Arial, Sans Bold 16px"
+                },
+                description: TextLimit::Lines {
+                    lines: 1, width, font: "Helvetica, Arial, Sans 14px"
+                }
+            },
+            Self::LinkedIn => SocialTextLimits {
+                title: TextLimit::Lines {
+                    lines: 2, width, font: "Segoe UI, Roboto, Sans Semi-Bold 14px"
+                },
+                description: TextLimit::None
+            },
+            Self::Mastodon => SocialTextLimits {
+                title: TextLimit::Lines {
+                    lines: 1, width, font: "Roboto, Sans Bold 15px"
+                },
+                description: TextLimit::Lines {
+                    lines: 1, width, font: "Roboto, Sans 14px"
+                }
+            },
+            Self::Twitter => SocialTextLimits {
+                title: TextLimit::Chars(70),
+                description: TextLimit::Lines {
+                    lines: match size {
+                        CardSize::Medium => 3,
+                        _ => 2
+                    },
+                    width,
+                    font: "Chirp, Helvetica, Sans 15px"
+                }
             }
         }
     }
 
+    /// Get the size kind of the biggest image the platform cards display
+    pub fn largest_image_kind(&self) -> SocialImageSizeKind {
+        match self {
+            Self::Discourse => SocialImageSizeKind::Small,
+            _ => SocialImageSizeKind::Large
+        }
+    }
+
+    /// Get the region of an image kept by a card, with the recommended aspect ratio
+    ///
+    /// Returns the (x, y, width, height) of the centered crop.
+    ///
+    pub fn image_crop(&self, kind: &SocialImageSizeKind, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let (crop_width, crop_height) = self.image_size(kind).recommended;
+        crop_to_ratio(crop_width as f64 / crop_height as f64, width, height)
+    }
+
     pub fn image_size(&self, kind: &SocialImageSizeKind) -> SocialImageConstraints {
         SocialImageConstraints {
             minimum: match self {
@@ -167,6 +278,16 @@ pub struct SocialConstraints {
     pub image_size: usize,
     /// Image allowed formats
     pub image_formats: Vec<ImageFormat>,
+    /// Animated images are played
+    pub image_animated: bool,
+    /// Image alternative text maximum length
+    pub image_alt_length: Option<usize>,
+    /// Favicon size in pixels, None if the favicon is not displayed
+    pub favicon_size: Option<u32>,
+    /// Properties using RDFa prefixes declared by the page are read
+    pub rdfa_prefixes: bool,
+    /// The last occurrence of a repeated tag is used instead of the first
+    pub meta_last_wins: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -177,6 +298,28 @@ pub struct SocialImageConstraints {
     pub recommended: (u32, u32),
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextLimit {
+    /// Text is displayed in full
+    None,
+    /// Text is cut after a number of characters
+    Chars(usize),
+    /// Text is cut after a number of lines rendered with the given width and font
+    Lines {
+        lines: i32,
+        width: i32,
+        font: &'static str
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct SocialTextLimits {
+    /// Title text limit
+    pub title: TextLimit,
+    /// Description text limit
+    pub description: TextLimit,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SocialImageSizeKind {
     Small,
@@ -0,0 +1,187 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use pangocairo::pango::{self, prelude::*};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::social::TextLimit;
+
+const ELLIPSIS: &str = "…";
+/// Invisible formatting characters, including bidirectional marks and controls
+///
+/// Zero width joiners are kept since they are part of emoji and some scripts.
+///
+const INVISIBLE_CHARS: [char; 13] = [
+    '\u{200B}', '\u{200E}', '\u{200F}',
+    '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}',
+    '\u{2066}', '\u{2067}', '\u{2068}', '\u{2069}', '\u{FEFF}',
+];
+
+/// Normalize a text extracted from the document
+///
+/// Strips markup, decodes HTML entities, removes invisible and bidirectional
+/// formatting characters and collapses whitespace.
+///
+pub fn normalize(text: &str) -> String {
+    let stripped = strip_tags(text);
+    let decoded = html_escape::decode_html_entities(&stripped);
+
+    decoded
+        .split(|c: char| INVISIBLE_CHARS.contains(&c))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Remove anything that looks like a markup tag from a text
+///
+/// A "<" is only the start of a tag if a ">" closes it, so comparisons like
+/// "a<b" are kept.
+///
+fn strip_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        let opens_tag = matches!(
+            rest[start + 1..].chars().next(), Some(next) if next.is_ascii_alphabetic() || next == '/' || next == '!'
+        );
+        let end = rest[start..].find('>').map(|end| start + end);
+
+        match (opens_tag, end) {
+            (true, Some(end)) => {
+                // Skip the tag, keeping only the text content
+                result.push_str(&rest[..start]);
+                rest = &rest[end + 1..];
+            },
+            _ => {
+                result.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// Count the user perceived characters of a text
+///
+/// Emoji sequences and combined characters count as one.
+///
+pub fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Truncate a text the way a platform would display it
+///
+/// Returns the truncated text with an ellipsis, or None if the text fits.
+///
+/// * `text` - The text to truncate
+/// * `limit` - The platform limit for the text
+///
+pub fn truncate(text: &str, limit: &TextLimit) -> Option<String> {
+    match limit {
+        TextLimit::None => None,
+        TextLimit::Chars(max) => {
+            if grapheme_count(text) <= *max {
+                return None;
+            }
+
+            let cut: String = text.graphemes(true).take(max.saturating_sub(1)).collect();
+            Some(format!("{}{}", cut.trim_end(), ELLIPSIS))
+        },
+        TextLimit::Lines { lines, width, font } => {
+            let layout = text_layout(*width, font);
+            layout.set_text(text);
+
+            if layout.line_count() <= *lines {
+                return None;
+            }
+
+            // Byte index where the last visible line ends
+            let end = match layout.line_readonly(lines - 1) {
+                Some(line) => (line.start_index() + line.length()) as usize,
+                None => text.len()
+            };
+
+            // Remove graphemes until the text and the ellipsis fit
+            let mut graphemes: Vec<&str> = text[..end].graphemes(true).collect();
+            loop {
+                let candidate = format!("{}{}", graphemes.concat().trim_end(), ELLIPSIS);
+                layout.set_text(&candidate);
+
+                if layout.line_count() <= *lines || graphemes.is_empty() {
+                    return Some(candidate);
+                }
+                graphemes.pop();
+            }
+        }
+    }
+}
+
+fn text_layout(width: i32, font: &str) -> pango::Layout {
+    let context = pangocairo::FontMap::default().create_context();
+    let layout = pango::Layout::new(&context);
+
+    layout.set_font_description(Some(&pango::FontDescription::from_string(font)));
+    layout.set_width(width * pango::SCALE);
+    layout.set_wrap(pango::WrapMode::WordChar);
+
+    layout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn truncate_chars(text: &str, max: usize) -> Option<String> {
+        truncate(text, &TextLimit::Chars(max))
+    }
+
+    #[test]
+    fn truncate_keeps_fitting_text() {
+        assert_eq!(truncate_chars("Share Preview", 13), None);
+        assert_eq!(truncate_chars("Share Preview", 20), None);
+        assert_eq!(truncate("Share Preview", &TextLimit::None), None);
+    }
+
+    #[test]
+    fn truncate_counts_ellipsis() {
+        assert_eq!(truncate_chars("Share Preview", 7), Some("Share…".to_string()));
+        assert_eq!(truncate_chars("Share Preview", 8), Some("Share P…".to_string()));
+    }
+
+    #[test]
+    fn truncate_keeps_graphemes_whole() {
+        // Family emoji joined with zero width joiners, and a combining accent
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let text = format!("{}{}e\u{0301}abc", family, family);
+
+        assert_eq!(grapheme_count(&text), 6);
+        assert_eq!(truncate_chars(&text, 6), None);
+        assert_eq!(truncate_chars(&text, 3), Some(format!("{}{}…", family, family)));
+        assert_eq!(truncate_chars(&text, 4), Some(format!("{}{}e\u{0301}…", family, family)));
+        assert_eq!(truncate_chars(&text, 0), Some("…".to_string()));
+    }
+
+    #[test]
+    fn normalize_strips_tags_and_decodes_entities() {
+        assert_eq!(normalize("<b>Fish</b> &amp; <i>Chips</i>"), "Fish & Chips");
+        assert_eq!(normalize("&lt;b&gt; is bold"), "<b> is bold");
+        assert_eq!(normalize("<!-- note -->Hello</p>"), "Hello");
+    }
+
+    #[test]
+    fn normalize_keeps_unclosed_less_than() {
+        assert_eq!(normalize("a<b and c < d"), "a<b and c < d");
+        assert_eq!(normalize("1 <3 you"), "1 <3 you");
+    }
+
+    #[test]
+    fn normalize_removes_invisible_chars_and_whitespace() {
+        assert_eq!(normalize("  Hello\u{200B}\n\t world\u{202E}  "), "Hello world");
+        assert_eq!(normalize("\u{FEFF}Title"), "Title");
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use scraper::{Html, Selector};
+use serde_json::Value;
+use url::Url;
+
+use super::{Data, CLIENT};
+
+const ACTIVITY_JSON: &str = "application/activity+json";
+
+/// Result of verifying a profile link of the page
+#[derive(Debug, Default, Clone)]
+pub struct ProfileVerification {
+    /// The rel="me" link of the page pointing to the profile, if any
+    pub link: Option<String>,
+    /// Whether the profile links back to the page, None if the profile couldn't be fetched
+    pub backlink: Option<bool>,
+}
+
+/// Verify a profile against the page rel="me" links
+///
+/// The page links are checked the way Mastodon's verifier does: a rel="me"
+/// link must match the profile URL ignoring case, or the first rel="me" link
+/// must redirect to it. Then the profile is fetched to check that it links
+/// back to the page.
+///
+/// * `data` - The scraped page data
+/// * `profile` - The profile URL, e.g. https://mastodon.social/@user
+///
+pub async fn verify_profile(data: &Data, profile: &Url) -> ProfileVerification {
+    let mut verification = ProfileVerification {
+        link: data.rel_me.iter()
+            .find(|link| same_link(link, profile.as_str()))
+            .cloned(),
+        ..ProfileVerification::default()
+    };
+
+    if verification.link.is_none() {
+        if let Some(first) = data.rel_me.first() {
+            if redirects_to(first, profile).await {
+                verification.link = Some(first.to_string());
+            }
+        }
+    }
+
+    // Profiles link to the URL users entered, not the one it redirects to
+    if let Some(page) = data.requested_url.as_ref().or(data.page_url.as_ref()) {
+        verification.backlink = profile_links_to(profile, page).await;
+    }
+
+    verification
+}
+
+/// Check if a profile links back to the page
+///
+/// Mastodon renders the profile fields on the client, so they are read from
+/// the ActivityPub actor first, then the links of the HTML profile are checked.
+///
+/// Returns None if the profile couldn't be fetched.
+///
+async fn profile_links_to(profile: &Url, page: &Url) -> Option<bool> {
+    let fields = actor_fields(profile).await;
+    if fields.iter().flatten().any(|value| links_to(value, page)) {
+        return Some(true);
+    }
+
+    let html = match CLIENT.get(profile).await {
+        Ok(mut resp) if resp.status().is_success() => resp.body_string().await.ok(),
+        _ => None
+    };
+
+    match html {
+        Some(html) => Some(links_to(&html, page)),
+        None => fields.map(|_| false)
+    }
+}
+
+/// Get the HTML values of the PropertyValue attachments of an ActivityPub actor
+async fn actor_fields(profile: &Url) -> Option<Vec<String>> {
+    // Skip the cache, the actor has the same URL as the HTML profile
+    let client = surf::Client::new().with(surf::middleware::Redirect::default());
+    let mut resp = client.get(profile).header("Accept", ACTIVITY_JSON).await.ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+
+    let json: Value = serde_json::from_str(&resp.body_string().await.ok()?).ok()?;
+    let fields = json["attachment"].as_array()?.iter()
+        .filter(|attachment| attachment["type"] == "PropertyValue")
+        .filter_map(|attachment| attachment["value"].as_str().map(|value| value.to_string()))
+        .collect();
+
+    Some(fields)
+}
+
+/// Compare two links ignoring case, like Mastodon does
+fn same_link(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+/// Check if a link redirects to the given URL in one hop
+async fn redirects_to(link: &str, target: &Url) -> bool {
+    let link = match Url::parse(link) {
+        Ok(link) => link,
+        Err(_) => return false
+    };
+
+    // Use a request without the redirect middleware to read the location
+    match surf::head(&link).await {
+        Ok(resp) if resp.status().is_redirection() => {
+            resp.header("Location")
+                .and_then(|location| link.join(location.last().as_str()).ok())
+                .map(|location| same_link(location.as_str(), target.as_str()))
+                .unwrap_or(false)
+        },
+        _ => false
+    }
+}
+
+/// Check if an HTML body links to the page, ignoring a trailing slash
+fn links_to(body: &str, page: &Url) -> bool {
+    let page = page.as_str().trim_end_matches('/');
+    let document = Html::parse_document(body);
+    let selector = Selector::parse("a[href], link[href]").unwrap();
+
+    document.select(&selector).any(|element| {
+        element.value().attr("href")
+            .map(|href| same_link(href.trim().trim_end_matches('/'), page))
+            .unwrap_or(false)
+    })
+}
@@ -19,6 +19,9 @@ mod imp {
         pub key: RefCell<String>,
         #[property(get, set)]
         pub value: RefCell<String>,
+        /// Description of the values conflicting with this one, empty if none
+        #[property(get, set)]
+        pub conflict: RefCell<String>,
     }
 
     #[glib::object_subclass]
@@ -1,17 +1,19 @@
 // Copyright 2021 Rafael Mardojai CM
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use adw::prelude::AdwDialogExt;
 use gettextrs::*;
 use gtk::{
     CompositeTemplate,
     glib,
-    gdk::Texture,
+    gdk::{self, Texture},
     prelude::*,
     subclass::prelude::*,
 };
 
 use crate::backend::{Card, CardError, CardSize, Social};
-use super::CardImage;
+use crate::i18n::gettext_f;
+use super::{CardImage, ImageDialog};
 
 mod imp {
     use super::*;
@@ -77,6 +79,9 @@ impl CardBox {
             },
             CardError::TwitterNoCardFound => {
                 gettext("Twitter: No card found.")
+            },
+            CardError::TwitterUnknownCard(kind) => {
+                gettext_f("Twitter: Unknown card type \"{name}\".", &[("name", kind)])
             }
         };
 
@@ -115,6 +120,36 @@ impl CardBox {
         if let Some(img_bytes) = &card.image {
             image.set_image(&img_bytes, &card.size);
         }
+        if let Some(animation) = &card.animation {
+            image.set_animation(animation);
+        }
+        image.set_alt(card.image_alt.as_deref());
+
+        // Open the original image in the inspector
+        if let Some(source) = &card.image_source {
+            let social = card.social.clone();
+            let size = card.size.clone();
+            let source = source.clone();
+            let click = gtk::GestureClick::new();
+            click.connect_released(move |gesture, _, _, _| {
+                let dialog = ImageDialog::new(&source, &social, &size);
+                dialog.present(gesture.widget().as_ref());
+            });
+
+            image.add_controller(click);
+            image.set_cursor_from_name(Some("pointer"));
+            image.set_tooltip_text(Some(&gettext("Inspect Image")));
+        }
+
+        // Render player cards with a poster and a play button
+        if let Some(_) = &card.player {
+            image.set_player(true);
+
+            if let None = &card.image {
+                image.set_fallback(&card.size);
+            }
+            image.set_visible(true);
+        }
 
         // Tweak card UI
         match &card.social {
@@ -130,6 +165,19 @@ impl CardBox {
                         favicon.set_visible(true);
                     }
                 }
+
+                // Site color strip, like the one of the onebox header
+                if let Some(Ok(color)) = card.accent_color.as_deref().map(gdk::RGBA::parse) {
+                    let accent: gtk::DrawingArea = builder.object("accent").expect("Couldn't get UI accent");
+                    accent.set_draw_func(move |_, cr, width, height| {
+                        cr.rectangle(0.0, 0.0, width as f64, height as f64);
+                        cr.set_source_rgba(
+                            color.red() as f64, color.green() as f64, color.blue() as f64, color.alpha() as f64
+                        );
+                        let _ = cr.fill();
+                    });
+                    accent.set_visible(true);
+                }
             },
             Social::Facebook => {
                 if let Some(_) = &card.image {
@@ -137,9 +185,7 @@ impl CardBox {
                 }
 
                 if let Some(_) = &card.description {
-                    if &card.title.chars().count() <= &65 {
-                        description.set_visible(true);
-                    }
+                    description.set_visible(true);
                 }
 
                 if let CardSize::Medium = card.size {
@@ -163,24 +209,30 @@ impl CardBox {
                 if let Some(_) = &card.description {
                     description.set_visible(true);
                 }
+
+                if let Some(handle) = &card.author {
+                    let author: gtk::Label = builder.object("author").expect("Couldn't get UI author");
+                    author.set_label(&gettext_f("More from {author}", &[("author", handle)]));
+                    author.set_visible(true);
+                }
             },
             Social::Twitter => {
                 if let Some(_) = &card.image {
                     image.set_visible(true);
                 }
 
+                if let Some(app) = &card.app {
+                    let app_box: gtk::Box = builder.object("app_box").expect("Couldn't get UI app box");
+                    let stores: gtk::Label = builder.object("stores").expect("Couldn't get UI stores");
+                    stores.set_label(&app.stores.join(" · "));
+                    app_box.set_visible(true);
+                }
+
                 if let Some(_) = &card.description {
                     description.set_visible(true);
                 }
-                match card.size {
-                    CardSize::Medium => {
-                        card_box.set_orientation(gtk::Orientation::Horizontal);
-                        description.set_lines(3);
-                    }
-                    CardSize::Large => {
-                        description.set_lines(2);
-                    }
-                    _ => {}
+                if let CardSize::Medium = card.size {
+                    card_box.set_orientation(gtk::Orientation::Horizontal);
                 }
             }
         }
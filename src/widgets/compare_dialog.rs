@@ -0,0 +1,169 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::rc::Rc;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gettextrs::*;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    glib::clone,
+};
+use gtk_macros::spawn;
+
+use crate::backend::{Data, Social};
+use crate::models::LogListModel;
+use super::{CardBox, LogDialog};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/rafaelmardojai/SharePreview/compare-dialog.ui")]
+    pub struct CompareDialog {
+        #[template_child]
+        pub grid: TemplateChild<gtk::FlowBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CompareDialog {
+        const NAME: &'static str = "CompareDialog";
+        type Type = super::CompareDialog;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for CompareDialog {}
+    impl WidgetImpl for CompareDialog {}
+    impl WindowImpl for CompareDialog {}
+    impl AdwWindowImpl for CompareDialog {}
+}
+
+glib::wrapper! {
+    pub struct CompareDialog(ObjectSubclass<imp::CompareDialog>)
+        @extends gtk::Widget, gtk::Window, adw::Window;
+}
+
+impl CompareDialog {
+    pub fn new(data: &Data) -> Self {
+        let dialog: Self = glib::Object::builder().build();
+
+        // Share the same data between all cards so images are fetched once
+        let data = Rc::new(data.clone());
+        for social in Social::all() {
+            dialog.add_social(social, data.clone());
+        }
+
+        dialog
+    }
+
+    /// Add a grid item for the given social and start building its card
+    fn add_social(&self, social: Social, data: Rc<Data>) {
+        let logger = LogListModel::new();
+
+        let container = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(6)
+            .build();
+
+        let header = gtk::Box::builder()
+            .spacing(6)
+            .build();
+        container.append(&header);
+
+        let title = gtk::Label::builder()
+            .label(social.to_string())
+            .hexpand(true)
+            .xalign(0.0)
+            .css_classes(["heading"])
+            .build();
+        header.append(&title);
+
+        let counts = gtk::Box::builder()
+            .spacing(3)
+            .build();
+        let war_count = adw::ButtonContent::builder()
+            .icon_name("dialog-warning-symbolic")
+            .build();
+        counts.append(&war_count);
+        let err_count = adw::ButtonContent::builder()
+            .icon_name("dialog-error-symbolic")
+            .build();
+        counts.append(&err_count);
+
+        let log_button = gtk::Button::builder()
+            .child(&counts)
+            .sensitive(false)
+            .tooltip_text(gettext("Show Logs"))
+            .css_classes(["flat"])
+            .build();
+        log_button.connect_clicked(
+            clone!(
+                #[weak(rename_to = dialog)]
+                self,
+                #[strong]
+                logger,
+                move |_| {
+                    let log_dialog = LogDialog::new(&logger);
+                    log_dialog.present(Some(&dialog));
+                }
+            )
+        );
+        header.append(&log_button);
+
+        let spinner = gtk::Spinner::builder()
+            .spinning(true)
+            .width_request(32)
+            .height_request(32)
+            .halign(gtk::Align::Center)
+            .valign(gtk::Align::Center)
+            .vexpand(true)
+            .build();
+        container.append(&spinner);
+
+        self.imp().grid.append(&container);
+
+        spawn!(clone!(
+            #[weak]
+            container,
+            async move {
+                let card = data.get_card(social, &logger).await;
+
+                let card = match card {
+                    Ok(card) => CardBox::new_from_card(&card),
+                    Err(error) => CardBox::new_from_error(&error)
+                };
+
+                container.remove(&spinner);
+                container.append(&card);
+
+                let (_, war, err) = logger.worrying_count();
+                CompareDialog::set_count(&war_count, war, "warning");
+                CompareDialog::set_count(&err_count, err, "error");
+                log_button.set_sensitive(true);
+            }
+        ));
+    }
+
+    fn set_count(content: &adw::ButtonContent, count: u32, css_class: &str) {
+        if count > 0 {
+            content.add_css_class(css_class);
+            content.remove_css_class("dim-label");
+        } else {
+            content.add_css_class("dim-label");
+            content.remove_css_class(css_class);
+        }
+
+        content.set_label(&count.to_string());
+    }
+}
@@ -1,16 +1,22 @@
 // Copyright 2021 Rafael Mardojai CM
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::cell::RefCell;
+
+use adw::prelude::*;
 use adw::subclass::prelude::*;
+use gettextrs::*;
 use gtk::{
     CompositeTemplate,
     gio,
     glib,
     glib::clone,
-    prelude::*,
 };
+use gtk_macros::spawn;
+use url::Url;
 
-use crate::backend::Data;
+use crate::backend::{Data, MetaDuplicate, Social, verify_profile};
+use crate::i18n::gettext_f;
 use crate::models::MetadataItem;
 
 mod imp {
@@ -19,6 +25,7 @@ mod imp {
     #[derive(Debug, CompositeTemplate)]
     #[template(resource = "/com/rafaelmardojai/SharePreview/data-dialog.ui")]
     pub struct DataDialog {
+        pub data: RefCell<Data>,
         pub model: gio::ListStore,
         pub images_model: gtk::StringList,
         #[template_child]
@@ -37,6 +44,32 @@ mod imp {
         pub list: TemplateChild<gtk::ListBox>,
         #[template_child]
         pub images_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub microdata_stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub microdata_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub icons_stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub icons_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub manifest_stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub manifest_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub profiles_stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub profiles_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub profile_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub link_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub link_icon: TemplateChild<gtk::Image>,
+        #[template_child]
+        pub backlink_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub backlink_icon: TemplateChild<gtk::Image>,
     }
 
     #[glib::object_subclass]
@@ -47,6 +80,7 @@ mod imp {
 
         fn new() -> Self {
             Self {
+                data: RefCell::new(Data::default()),
                 model: gio::ListStore::new::<MetadataItem>(),
                 images_model: gtk::StringList::default(),
                 search: TemplateChild::default(),
@@ -57,11 +91,25 @@ mod imp {
                 images_stack: TemplateChild::default(),
                 list: TemplateChild::default(),
                 images_list: TemplateChild::default(),
+                microdata_stack: TemplateChild::default(),
+                microdata_box: TemplateChild::default(),
+                icons_stack: TemplateChild::default(),
+                icons_list: TemplateChild::default(),
+                manifest_stack: TemplateChild::default(),
+                manifest_list: TemplateChild::default(),
+                profiles_stack: TemplateChild::default(),
+                profiles_list: TemplateChild::default(),
+                profile_entry: TemplateChild::default(),
+                link_row: TemplateChild::default(),
+                link_icon: TemplateChild::default(),
+                backlink_row: TemplateChild::default(),
+                backlink_icon: TemplateChild::default(),
             }
         }
 
         fn class_init(klass: &mut Self::Class) {
             klass.bind_template();
+            klass.bind_template_instance_callbacks();
         }
 
         // You must call `Widget`'s `init_template()` within `instance_init()`.
@@ -81,12 +129,18 @@ glib::wrapper! {
         @extends gtk::Widget, gtk::Window, adw::Window;
 }
 
+#[gtk::template_callbacks]
 impl DataDialog {
     pub fn new(data: &Data) -> Self {
         let dialog: Self = glib::Object::builder().build();
 
         dialog.set_metadata(&data);
         dialog.set_images(&data);
+        dialog.set_microdata(&data);
+        dialog.set_icons(&data);
+        dialog.set_manifest(&data);
+        dialog.set_profiles(&data);
+        dialog.imp().data.replace(data.clone());
 
         dialog
     }
@@ -101,17 +155,15 @@ impl DataDialog {
         self.imp().title.set_label(&site_title);
         self.imp().url.set_label(&data.url);
 
+        // Repeated tags with different values, platforms may pick a different one
+        let conflicts: Vec<MetaDuplicate> = data.duplicates().into_iter()
+            .filter(|duplicate| duplicate.is_conflicting())
+            .collect();
+
         // imp.model.remove_all(); // Remove previous model items
         // Add new items from HashMap:
         for meta in data.metadata.iter() {
-            let mut names: Vec<String> = Vec::new();
-
-            if let Some(val) = &meta.name {
-                names.push(val.to_string());
-            }
-            names.append(&mut meta.property.clone());
-
-            let name = names.join(" ");
+            let name = meta.key();
 
             let content = match &meta.content {
                 Some(val) => val.to_string(),
@@ -119,6 +171,13 @@ impl DataDialog {
             };
 
             let item = MetadataItem::new(&name, &content);
+            let names = meta.names();
+            let conflict = conflicts.iter()
+                .filter(|conflict| names.contains(&conflict.key))
+                .map(DataDialog::conflict_description)
+                .collect::<Vec<String>>()
+                .join("\n\n");
+            item.set_conflict(conflict);
             self.imp().model.append(&item);
         }
 
@@ -163,10 +222,19 @@ impl DataDialog {
                 #[upgrade_or_panic]
                 move |item| {
                     let item = item.downcast_ref::<MetadataItem>().expect("Couldn't get MetadataItem");
-                    obj.metadata_row(
+                    let row = obj.metadata_row(
                         Some(&item.property::<String>("key")),
                         Some(&item.property::<String>("value"))
-                    )
+                    );
+
+                    // Highlight conflicting values
+                    let conflict = item.conflict();
+                    if !conflict.is_empty() {
+                        row.add_css_class("conflict");
+                        row.set_tooltip_text(Some(&conflict));
+                    }
+
+                    row
                 }
             )
         );
@@ -189,6 +257,21 @@ impl DataDialog {
         filter_model.items_changed(0, 0, 0);
     }
 
+    /// Describe the value each platform picks from repeated tags
+    fn conflict_description(conflict: &MetaDuplicate) -> String {
+        let mut lines = vec![gettext_f(
+            "\"{name}\" is repeated with different values, platforms will use:",
+            &[("name", &conflict.key)]
+        )];
+
+        for social in Social::all() {
+            let value = conflict.pick(social.constraints().meta_last_wins);
+            lines.push(format!("{}: \"{}\"", social, value));
+        }
+
+        lines.join("\n")
+    }
+
     pub fn set_images(&self, data: &Data) {
         let images_stack = &*self.imp().images_stack;
 
@@ -248,6 +331,167 @@ impl DataDialog {
         filter_model.items_changed(0, 0, 0);
     }
 
+    pub fn set_microdata(&self, data: &Data) {
+        let imp = self.imp();
+
+        // One group of properties per item, titled by the item type
+        for item in data.microdata.iter() {
+            let group = adw::PreferencesGroup::new();
+            match &item.itemtype {
+                Some(itemtype) => group.set_title(itemtype),
+                None => group.set_title(&gettext("Without Item Type"))
+            }
+
+            let list = gtk::ListBox::new();
+            list.set_selection_mode(gtk::SelectionMode::None);
+            list.add_css_class("content");
+            for property in item.properties.iter() {
+                list.append(&self.metadata_row(Some(&property.name), Some(&property.value)));
+            }
+
+            group.add(&list);
+            imp.microdata_box.append(&group);
+        }
+
+        if data.microdata.is_empty() {
+            imp.microdata_stack.set_visible_child_name("empty");
+        } else {
+            imp.microdata_stack.set_visible_child_name("list");
+        }
+    }
+
+    pub fn set_icons(&self, data: &Data) {
+        let imp = self.imp();
+
+        let manifest_icons = data.manifest.iter().flat_map(|manifest| manifest.icons.iter());
+        let mut empty = true;
+
+        for icon in data.icons.iter().chain(manifest_icons) {
+            imp.icons_list.append(&self.metadata_row(Some(&icon.key()), Some(&icon.image.url.to_string())));
+            empty = false;
+        }
+
+        if empty {
+            imp.icons_stack.set_visible_child_name("empty");
+        } else {
+            imp.icons_stack.set_visible_child_name("list");
+        }
+    }
+
+    pub fn set_manifest(&self, data: &Data) {
+        let imp = self.imp();
+
+        match &data.manifest {
+            Some(manifest) => {
+                let members = [
+                    ("url", manifest.url.as_ref().map(|url| url.to_string())),
+                    ("name", manifest.name.clone()),
+                    ("short_name", manifest.short_name.clone()),
+                    ("theme_color", manifest.theme_color.clone()),
+                ];
+
+                for (member, value) in members.iter() {
+                    if let Some(value) = value {
+                        imp.manifest_list.append(&self.metadata_row(Some(&member.to_string()), Some(value)));
+                    }
+                }
+
+                imp.manifest_stack.set_visible_child_name("list");
+            },
+            None => imp.manifest_stack.set_visible_child_name("empty")
+        }
+    }
+
+    pub fn set_profiles(&self, data: &Data) {
+        let imp = self.imp();
+
+        for link in data.rel_me.iter() {
+            imp.profiles_list.append(&self.metadata_row(None, Some(link)));
+        }
+
+        if data.rel_me.is_empty() {
+            imp.profiles_stack.set_visible_child_name("empty");
+        } else {
+            imp.profiles_stack.set_visible_child_name("list");
+        }
+    }
+
+    #[template_callback]
+    fn on_verify(&self) {
+        let imp = self.imp();
+
+        let profile = match Url::parse(imp.profile_entry.text().trim()) {
+            Ok(url) => {
+                imp.profile_entry.remove_css_class("error");
+                url
+            },
+            Err(_) => {
+                imp.profile_entry.add_css_class("error");
+                return;
+            }
+        };
+
+        imp.profile_entry.set_sensitive(false);
+        imp.link_row.set_visible(false);
+        imp.backlink_row.set_visible(false);
+
+        let spawn = clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move || {
+                spawn!(async move {
+                    let imp = dialog.imp();
+                    let data = imp.data.borrow().clone();
+
+                    let verification = verify_profile(&data, &profile).await;
+
+                    match &verification.link {
+                        Some(link) => DataDialog::set_check(
+                            &imp.link_row, &imp.link_icon, true, link
+                        ),
+                        None => DataDialog::set_check(
+                            &imp.link_row, &imp.link_icon, false,
+                            &gettext("No rel=\"me\" link matches the profile URL, only the case is ignored.")
+                        )
+                    }
+
+                    match verification.backlink {
+                        Some(true) => DataDialog::set_check(
+                            &imp.backlink_row, &imp.backlink_icon, true,
+                            &gettext("The profile links to this page.")
+                        ),
+                        Some(false) => DataDialog::set_check(
+                            &imp.backlink_row, &imp.backlink_icon, false,
+                            &gettext("This page URL was not found on the profile.")
+                        ),
+                        None => DataDialog::set_check(
+                            &imp.backlink_row, &imp.backlink_icon, false,
+                            &gettext("Couldn’t connect to the given profile URL.")
+                        )
+                    }
+
+                    imp.profile_entry.set_sensitive(true);
+                });
+            }
+        );
+        spawn();
+    }
+
+    fn set_check(row: &adw::ActionRow, icon: &gtk::Image, passed: bool, message: &str) {
+        row.set_subtitle(message);
+        row.set_visible(true);
+
+        if passed {
+            icon.set_icon_name(Some("emblem-ok-symbolic"));
+            icon.remove_css_class("error");
+            icon.add_css_class("success");
+        } else {
+            icon.set_icon_name(Some("dialog-warning-symbolic"));
+            icon.remove_css_class("success");
+            icon.add_css_class("error");
+        }
+    }
+
     pub fn metadata_row(&self, key: Option<&String>, value: Option<&String>) -> gtk::Widget {
         let builder = gtk::Builder::from_resource("/com/rafaelmardojai/SharePreview/metadata-item.ui");
         let row: gtk::ListBoxRow = builder.object("row").expect("Couldn't get widget");
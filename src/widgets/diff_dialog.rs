@@ -0,0 +1,307 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::rc::Rc;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gettextrs::*;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    glib::clone,
+};
+use gtk_macros::spawn;
+use url::Url;
+
+use crate::backend::{Data, Diff, DiffKind, Error, Social};
+use crate::i18n::gettext_f;
+use crate::models::LogListModel;
+use super::CardBox;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/rafaelmardojai/SharePreview/diff-dialog.ui")]
+    pub struct DiffDialog {
+        #[template_child]
+        pub url_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub old_entry: TemplateChild<gtk::Entry>,
+        #[template_child]
+        pub new_entry: TemplateChild<gtk::Entry>,
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub spinner: TemplateChild<gtk::Spinner>,
+        #[template_child]
+        pub error_page: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub meta_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub cards_box: TemplateChild<gtk::Box>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for DiffDialog {
+        const NAME: &'static str = "DiffDialog";
+        type Type = super::DiffDialog;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for DiffDialog {}
+    impl WidgetImpl for DiffDialog {}
+    impl WindowImpl for DiffDialog {}
+    impl AdwWindowImpl for DiffDialog {}
+}
+
+glib::wrapper! {
+    pub struct DiffDialog(ObjectSubclass<imp::DiffDialog>)
+        @extends gtk::Widget, gtk::Window, adw::Window;
+}
+
+#[gtk::template_callbacks]
+impl DiffDialog {
+    pub fn new(url: &str) -> Self {
+        let dialog: Self = glib::Object::builder().build();
+
+        dialog.imp().old_entry.set_text(url);
+        if url.is_empty() {
+            dialog.imp().old_entry.grab_focus();
+        } else {
+            dialog.imp().new_entry.grab_focus();
+        }
+
+        dialog
+    }
+
+    #[template_callback]
+    fn on_compare(&self) {
+        let imp = self.imp();
+
+        let old_url = DiffDialog::parse_entry(&imp.old_entry);
+        let new_url = DiffDialog::parse_entry(&imp.new_entry);
+
+        if let (Some(old_url), Some(new_url)) = (old_url, new_url) {
+            imp.url_box.set_sensitive(false);
+            imp.stack.set_visible_child_name("loading");
+            imp.spinner.start();
+
+            let spawn = clone!(
+                #[weak(rename_to = dialog)]
+                self,
+                move || {
+                    spawn!(async move {
+                        let imp = dialog.imp();
+
+                        // Scrape both URLs at the same time
+                        let ctx = glib::MainContext::default();
+                        let old_handle = ctx.spawn_local(async move { Data::from_url(&old_url, false).await });
+                        let new_handle = ctx.spawn_local(async move { Data::from_url(&new_url, false).await });
+                        let old_result = old_handle.await.expect("Couldn't scrape first URL");
+                        let new_result = new_handle.await.expect("Couldn't scrape second URL");
+
+                        match (old_result, new_result) {
+                            (Ok(old), Ok(new)) => {
+                                dialog.set_results(old, new);
+                                imp.stack.set_visible_child_name("results");
+                            },
+                            (Err(error), _) | (_, Err(error)) => {
+                                let (title, message) = DiffDialog::error_texts(&error);
+                                imp.error_page.set_title(&title);
+                                imp.error_page.set_description(Some(&message));
+                                imp.stack.set_visible_child_name("error");
+                            }
+                        }
+
+                        imp.spinner.stop();
+                        imp.url_box.set_sensitive(true);
+                    });
+                }
+            );
+            spawn();
+        }
+    }
+
+    fn set_results(&self, old: Data, new: Data) {
+        let imp = self.imp();
+
+        // Clear previous results
+        imp.meta_list.remove_all();
+        while let Some(child) = imp.cards_box.first_child() {
+            imp.cards_box.remove(&child);
+        }
+
+        for diff in Diff::metadata(&old, &new).iter() {
+            imp.meta_list.append(&DiffDialog::diff_row(diff));
+        }
+
+        let old = Rc::new(old);
+        let new = Rc::new(new);
+        for social in Social::all() {
+            self.add_social(social, old.clone(), new.clone());
+        }
+    }
+
+    /// Add a section comparing the cards of both URLs for the given social
+    fn add_social(&self, social: Social, old: Rc<Data>, new: Rc<Data>) {
+        let section = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .build();
+
+        let title = gtk::Label::builder()
+            .label(social.to_string())
+            .xalign(0.0)
+            .css_classes(["title-4"])
+            .build();
+        section.append(&title);
+
+        let cards = gtk::Box::builder()
+            .spacing(12)
+            .homogeneous(true)
+            .build();
+        section.append(&cards);
+
+        let spinner = gtk::Spinner::builder()
+            .spinning(true)
+            .width_request(32)
+            .height_request(32)
+            .halign(gtk::Align::Center)
+            .build();
+        cards.append(&spinner);
+
+        self.imp().cards_box.append(&section);
+
+        spawn!(clone!(
+            #[weak]
+            section,
+            async move {
+                // Cards logs are not shown when comparing
+                let old_card = old.get_card(social.clone(), &LogListModel::new()).await;
+                let new_card = new.get_card(social, &LogListModel::new()).await;
+
+                cards.remove(&spinner);
+                for card in [&old_card, &new_card] {
+                    let card_box = match card {
+                        Ok(card) => CardBox::new_from_card(card),
+                        Err(error) => CardBox::new_from_error(error)
+                    };
+                    cards.append(&card_box);
+                }
+
+                if let (Ok(old_card), Ok(new_card)) = (&old_card, &new_card) {
+                    let list = gtk::ListBox::builder()
+                        .selection_mode(gtk::SelectionMode::None)
+                        .css_classes(["content"])
+                        .build();
+
+                    for diff in Diff::cards(old_card, new_card).iter() {
+                        if diff.kind != DiffKind::Unchanged {
+                            list.append(&DiffDialog::diff_row(diff));
+                        }
+                    }
+
+                    if list.first_child().is_some() {
+                        section.append(&list);
+                    }
+                }
+            }
+        ));
+    }
+
+    fn diff_row(diff: &Diff) -> gtk::Widget {
+        let builder = gtk::Builder::from_resource("/com/rafaelmardojai/SharePreview/metadata-item.ui");
+        let row: gtk::ListBoxRow = builder.object("row").expect("Couldn't get widget");
+        let key_label: gtk::Label = builder.object("key").expect("Couldn't get widget");
+        let value_label: gtk::Label = builder.object("value").expect("Couldn't get widget");
+
+        key_label.set_label(&diff.key);
+
+        let old = diff.old.clone().unwrap_or_default();
+        let new = diff.new.clone().unwrap_or_default();
+        let value = match diff.kind {
+            DiffKind::Added => new,
+            DiffKind::Removed => old,
+            DiffKind::Changed => format!("{} → {}", old, new),
+            DiffKind::Unchanged => old,
+        };
+        if value.is_empty() {
+            value_label.set_visible(false);
+        } else {
+            value_label.set_label(&value);
+        }
+
+        match diff.kind {
+            DiffKind::Added => {
+                row.add_css_class("diff-added");
+                row.set_tooltip_text(Some(&gettext("Added")));
+            },
+            DiffKind::Removed => {
+                row.add_css_class("diff-removed");
+                row.set_tooltip_text(Some(&gettext("Removed")));
+            },
+            DiffKind::Changed => {
+                row.add_css_class("diff-changed");
+                row.set_tooltip_text(Some(&gettext("Changed")));
+            },
+            DiffKind::Unchanged => {}
+        }
+
+        row.upcast::<gtk::Widget>()
+    }
+
+    /// Get a valid URL from an entry, highlighting the entry if invalid
+    fn parse_entry(entry: &gtk::Entry) -> Option<Url> {
+        let mut url = entry.text().trim().to_string();
+
+        if url.is_empty() {
+            entry.add_css_class("error");
+            return None;
+        }
+
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            url.insert_str(0, "http://");
+        }
+        entry.set_text(&url);
+
+        match Url::parse(&url) {
+            Ok(url) => {
+                entry.remove_css_class("error");
+                Some(url)
+            },
+            Err(_) => {
+                entry.add_css_class("error");
+                None
+            }
+        }
+    }
+
+    fn error_texts(error: &Error) -> (String, String) {
+        match error {
+            Error::NetworkError(_) => (
+                gettext("Network Error"),
+                gettext("Couldn’t connect to the given URL.")
+            ),
+            Error::Unexpected(status) => (
+                gettext("Unexpected Error"),
+                if !status.is_empty() {
+                    gettext_f("Server Error {status}",  &[("status", status)])
+                } else {
+                    gettext("Couldn’t connect to the given URL.")
+                }
+            )
+        }
+    }
+}
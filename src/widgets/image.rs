@@ -1,15 +1,18 @@
 // Copyright 2021 Rafael Mardojai CM
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::{rc::Rc, time::Duration};
+
 use gtk::{
     CompositeTemplate,
     gdk::Texture,
     glib,
+    glib::clone,
     prelude::*,
     subclass::prelude::*
 };
 
-use crate::backend::CardSize;
+use crate::backend::{AnimationFrame, CardSize};
 
 mod imp {
     use super::*;
@@ -25,6 +28,8 @@ mod imp {
         pub fallback_icon: TemplateChild<gtk::Image>,
         #[template_child]
         pub image: TemplateChild<gtk::Picture>,
+        #[template_child]
+        pub play_icon: TemplateChild<gtk::Image>,
     }
 
     #[glib::object_subclass]
@@ -54,7 +59,8 @@ mod imp {
 
 glib::wrapper! {
     pub struct CardImage(ObjectSubclass<imp::CardImage>)
-        @extends gtk::Widget, gtk::Box;
+        @extends gtk::Widget, gtk::Box,
+        @implements gtk::Accessible;
 }
 
 impl CardImage {
@@ -80,6 +86,54 @@ impl CardImage {
         }
     }
 
+    /// Set the image alternative text as its accessible description
+    pub fn set_alt(&self, alt: Option<&str>) {
+        self.imp().image.set_alternative_text(alt);
+
+        match alt {
+            Some(alt) => self.update_property(&[gtk::accessible::Property::Description(alt)]),
+            None => self.reset_property(gtk::AccessibleProperty::Description)
+        }
+    }
+
+    /// Play the frames of an animated image
+    pub fn set_animation(&self, animation: &[AnimationFrame]) {
+        let frames: Vec<(Texture, u32)> = animation.iter()
+            .filter_map(|frame| {
+                Texture::from_bytes(&glib::Bytes::from(&frame.bytes))
+                    .ok()
+                    .map(|texture| (texture, frame.delay))
+            })
+            .collect();
+
+        if frames.len() > 1 {
+            self.show_frame(Rc::new(frames), 0);
+        }
+    }
+
+    fn show_frame(&self, frames: Rc<Vec<(Texture, u32)>>, index: usize) {
+        let (texture, delay) = &frames[index];
+        self.imp().image.set_paintable(Some(texture));
+
+        let delay = Duration::from_millis(*delay as u64);
+        glib::timeout_add_local_once(delay, clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move || {
+                obj.show_frame(frames.clone(), (index + 1) % frames.len());
+            }
+        ));
+    }
+
+    /// Show a play button over the image for player cards
+    pub fn set_player(&self, player: bool) {
+        self.imp().play_icon.set_visible(player);
+
+        if player {
+            self.imp().fallback_icon.set_icon_name(Some("video-x-generic-symbolic"));
+        }
+    }
+
     pub fn set_fallback(&self, size: &CardSize) {
         let (width, height) = size.image_size(); // Get image size
 
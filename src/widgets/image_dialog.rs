@@ -0,0 +1,228 @@
+// Copyright 2026 Rafael Mardojai CM
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gettextrs::*;
+use gtk::{
+    CompositeTemplate,
+    cairo,
+    gdk::Texture,
+    glib,
+    glib::clone,
+};
+use gtk_macros::spawn;
+use human_bytes::human_bytes;
+
+use crate::backend::{CardSize, Image, Social, card::safe_zone};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/rafaelmardojai/SharePreview/image-dialog.ui")]
+    pub struct ImageDialog {
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub spinner: TemplateChild<gtk::Spinner>,
+        #[template_child]
+        pub picture: TemplateChild<gtk::Picture>,
+        #[template_child]
+        pub crop_area: TemplateChild<gtk::DrawingArea>,
+        #[template_child]
+        pub error_page: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub url_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub format_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub size_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub dimensions_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub ratio_row: TemplateChild<adw::ActionRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImageDialog {
+        const NAME: &'static str = "ImageDialog";
+        type Type = super::ImageDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ImageDialog {}
+    impl WidgetImpl for ImageDialog {}
+    impl AdwDialogImpl for ImageDialog {}
+}
+
+glib::wrapper! {
+    pub struct ImageDialog(ObjectSubclass<imp::ImageDialog>)
+        @extends gtk::Widget, adw::Dialog;
+}
+
+impl ImageDialog {
+    pub fn new(image: &Image, social: &Social, size: &CardSize) -> Self {
+        let dialog: Self = glib::Object::builder().build();
+        let imp = dialog.imp();
+
+        imp.url_row.set_subtitle(image.url.as_str());
+        imp.stack.set_visible_child_name("loading");
+
+        let image = image.clone();
+        let social = social.clone();
+        let size = size.clone();
+        let spawn = clone!(
+            #[weak]
+            dialog,
+            move || {
+                spawn!(async move {
+                    let imp = dialog.imp();
+
+                    match image.png().await {
+                        Ok(bytes) => {
+                            match Texture::from_bytes(&glib::Bytes::from(&bytes)) {
+                                Ok(texture) => {
+                                    dialog.set_details(&image, &texture);
+                                    dialog.set_crop(&texture, social, size);
+                                    imp.picture.set_paintable(Some(&texture));
+                                    imp.stack.set_visible_child_name("image");
+                                },
+                                Err(err) => dialog.set_error(&err.to_string())
+                            }
+                        },
+                        Err(err) => dialog.set_error(&err.to_string())
+                    }
+
+                    imp.spinner.stop();
+                });
+            }
+        );
+        spawn();
+
+        dialog
+    }
+
+    fn set_details(&self, image: &Image, texture: &Texture) {
+        let imp = self.imp();
+        let (width, height) = (texture.width() as u32, texture.height() as u32);
+
+        let format = match image.format.get() {
+            Some(format) => format!("{:?}", format).to_uppercase(),
+            None => gettext("Unknown")
+        };
+        imp.format_row.set_subtitle(&format);
+
+        if let Some(size) = image.size.get() {
+            imp.size_row.set_subtitle(&human_bytes(size as f64));
+        }
+
+        imp.dimensions_row.set_subtitle(&format!("{}×{}px", width, height));
+
+        let divisor = gcd(width, height).max(1);
+        imp.ratio_row.set_subtitle(&format!(
+            "{}:{} ({:.2}:1)",
+            width / divisor,
+            height / divisor,
+            width as f64 / height.max(1) as f64
+        ));
+    }
+
+    fn set_error(&self, message: &str) {
+        let imp = self.imp();
+
+        imp.error_page.set_description(Some(message));
+        imp.stack.set_visible_child_name("error");
+    }
+
+    /// Draw the crop region of each platform over the image
+    ///
+    /// The platform of the card uses the card size, the others the biggest
+    /// image their cards display.
+    ///
+    fn set_crop(&self, texture: &Texture, social: Social, size: CardSize) {
+        let (width, height) = (texture.width() as u32, texture.height() as u32);
+
+        let crops: Vec<(Social, (u32, u32, u32, u32))> = Social::all().iter()
+            .map(|other| {
+                let kind = if *other == social { size.image_kind() } else { other.largest_image_kind() };
+                (other.clone(), other.image_crop(&kind, width, height))
+            })
+            .collect();
+        let zone = safe_zone(&crops.iter().map(|(_, crop)| *crop).collect::<Vec<_>>(), width, height);
+
+        self.imp().crop_area.set_draw_func(move |_, cr, area_width, area_height| {
+            // Place the regions where the picture draws the image
+            let scale = (area_width as f64 / width as f64).min(area_height as f64 / height as f64);
+            let offset_x = (area_width as f64 - width as f64 * scale) / 2.0;
+            let offset_y = (area_height as f64 - height as f64 * scale) / 2.0;
+            let rectangle = |cr: &cairo::Context, (x, y, w, h): (u32, u32, u32, u32)| {
+                cr.rectangle(
+                    offset_x + x as f64 * scale,
+                    offset_y + y as f64 * scale,
+                    w as f64 * scale,
+                    h as f64 * scale
+                );
+            };
+
+            let crop = crops.iter()
+                .find(|(other, _)| *other == social)
+                .map(|(_, crop)| *crop)
+                .unwrap_or((0, 0, width, height));
+
+            // Dim the parts of the image cut off by this card
+            cr.set_fill_rule(cairo::FillRule::EvenOdd);
+            rectangle(cr, (0, 0, width, height));
+            rectangle(cr, crop);
+            cr.set_source_rgba(0.0, 0.0, 0.0, 0.5);
+            let _ = cr.fill();
+
+            // Highlight the region visible in every platform
+            rectangle(cr, zone);
+            cr.set_source_rgba(0.18, 0.76, 0.49, 0.25);
+            let _ = cr.fill();
+
+            // Frame and label the region of each platform, this card last
+            cr.set_line_width(2.0);
+            cr.set_font_size(12.0);
+            let mut labeled: Vec<(u32, u32, u32, u32)> = Vec::new();
+            let ordered = crops.iter()
+                .filter(|(other, _)| *other != social)
+                .chain(crops.iter().filter(|(other, _)| *other == social));
+
+            for (other, other_crop) in ordered {
+                if *other == social {
+                    cr.set_dash(&[], 0.0);
+                    cr.set_source_rgba(0.21, 0.52, 0.89, 1.0);
+                } else {
+                    cr.set_dash(&[6.0, 4.0], 0.0);
+                    cr.set_source_rgba(1.0, 1.0, 1.0, 0.8);
+                }
+                rectangle(cr, *other_crop);
+                let _ = cr.stroke();
+
+                // Stack the labels of platforms sharing the same region
+                let line = labeled.iter().filter(|region| *region == other_crop).count();
+                labeled.push(*other_crop);
+                cr.move_to(
+                    offset_x + other_crop.0 as f64 * scale + 6.0,
+                    offset_y + other_crop.1 as f64 * scale + 16.0 + line as f64 * 14.0
+                );
+                let _ = cr.show_text(&other.to_string());
+            }
+        });
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
@@ -1,11 +1,17 @@
 mod card;
+mod compare_dialog;
 mod image;
+mod image_dialog;
 mod log_dialog;
 mod data_dialog;
+mod diff_dialog;
 
 pub use self::{
     card::CardBox,
+    compare_dialog::CompareDialog,
     image::CardImage,
+    image_dialog::ImageDialog,
     log_dialog::LogDialog,
-    data_dialog::DataDialog
+    data_dialog::DataDialog,
+    diff_dialog::DiffDialog
 };
@@ -1,6 +1,7 @@
 use std::{
-    cell::RefCell,
-    str::FromStr
+    cell::{Cell, RefCell},
+    str::FromStr,
+    time::{Duration, Instant}
 };
 
 use adw::prelude::*;
@@ -18,13 +19,18 @@ use url::Url;
 
 use crate::{
     application::SharePreviewApplication,
-    backend::{Data, Error, Social, Log},
+    backend::{Data, Error, Social, Log, page_version},
     config::{APP_ID, PROFILE},
     i18n::gettext_f,
     models::LogListModel,
-    widgets::{CardBox, DataDialog, LogDialog}
+    widgets::{CardBox, CompareDialog, DataDialog, DiffDialog, LogDialog}
 };
 
+/// Seconds between watch mode checks
+const WATCH_INTERVAL: u32 = 2;
+/// Seconds between watch mode scrapes of pages without ETag or Last-Modified
+const WATCH_UNVALIDATED_INTERVAL: u64 = 30;
+
 mod imp {
     use super::*;
 
@@ -36,11 +42,17 @@ mod imp {
         pub card: RefCell<Option<CardBox>>,
         pub data: RefCell<Data>,
         pub active_url: RefCell<String>,
+        pub watch_source: RefCell<Option<glib::SourceId>>,
+        pub watch_version: RefCell<Option<String>>,
+        pub watch_busy: Cell<bool>,
+        pub watch_scraped: Cell<Option<Instant>>,
         #[template_child]
         pub toasts: TemplateChild<adw::ToastOverlay>,
         #[template_child]
         pub color_scheme: TemplateChild<gtk::Button>,
         #[template_child]
+        pub watch: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
         pub social: TemplateChild<gtk::DropDown>,
         #[template_child]
         pub url_box: TemplateChild<gtk::Box>,
@@ -57,6 +69,8 @@ mod imp {
         #[template_child]
         pub error_message: TemplateChild<gtk::Label>,
         #[template_child]
+        pub card_scroll: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
         pub cardbox: TemplateChild<gtk::Box>,
         #[template_child]
         pub war_count: TemplateChild<adw::ButtonContent>,
@@ -79,8 +93,13 @@ mod imp {
                 card: RefCell::new(Option::default()),
                 data: RefCell::new(Data::default()),
                 active_url: RefCell::new(String::default()),
+                watch_source: RefCell::new(Option::default()),
+                watch_version: RefCell::new(Option::default()),
+                watch_busy: Cell::new(false),
+                watch_scraped: Cell::new(None),
                 toasts: TemplateChild::default(),
                 color_scheme: TemplateChild::default(),
+                watch: TemplateChild::default(),
                 social: TemplateChild::default(),
                 url_box: TemplateChild::default(),
                 url_entry: TemplateChild::default(),
@@ -89,6 +108,7 @@ mod imp {
                 spinner: TemplateChild::default(),
                 error_title: TemplateChild::default(),
                 error_message: TemplateChild::default(),
+                card_scroll: TemplateChild::default(),
                 cardbox: TemplateChild::default(),
                 war_count: TemplateChild::default(),
                 err_count: TemplateChild::default(),
@@ -104,6 +124,10 @@ mod imp {
                 win.run();
             });
 
+            klass.install_action("win.reload", None, move |win, _, _| {
+                win.load(true);
+            });
+
             klass.install_action("win.metadata", None, move |win, _, _| {
                 win.show_metadata();
             });
@@ -112,6 +136,14 @@ mod imp {
                 win.show_log();
             });
 
+            klass.install_action("win.compare", None, move |win, _, _| {
+                win.show_compare();
+            });
+
+            klass.install_action("win.diff", None, move |win, _, _| {
+                win.show_diff();
+            });
+
             klass.install_action("win.url", None, move |win, _, _| {
                 win.imp().url_entry.grab_focus();
             });
@@ -210,6 +242,14 @@ impl SharePreviewWindow {
     }
 
     fn run(&self) {
+        self.load(false);
+    }
+
+    /// Scrape the URL in the entry and show its card
+    ///
+    /// * `reload` - Don't use cached responses
+    ///
+    fn load(&self, reload: bool) {
         let imp = self.imp();
 
         if !imp.url_entry.text().is_empty() {
@@ -234,10 +274,13 @@ impl SharePreviewWindow {
                         move || {
                             spawn!(async move {
                                 let imp = win.imp();
-                                match Data::from_url(&url).await {
+                                match Data::from_url(&url, reload).await {
                                     Ok(data) => {
                                         imp.data.replace(data);
                                         imp.active_url.replace(url.to_string());
+                                        imp.watch_version.replace(None);
+                                        imp.watch_scraped.set(Some(Instant::now()));
+                                        imp.card_scroll.vadjustment().set_value(0.0);
                                         win.update_card().await;
                                         imp.stack.set_visible_child_name("card");
                                     }
@@ -284,6 +327,20 @@ impl SharePreviewWindow {
         dialog.present();
     }
 
+    fn show_compare(&self) {
+        let data = self.imp().data.borrow();
+        let dialog = CompareDialog::new(&data);
+        dialog.set_transient_for(Some(self));
+        dialog.present();
+    }
+
+    fn show_diff(&self) {
+        let active_url = self.imp().active_url.borrow();
+        let dialog = DiffDialog::new(&active_url);
+        dialog.set_transient_for(Some(self));
+        dialog.present();
+    }
+
     fn show_log(&self) {
         let dialog = LogDialog::new(&self.imp().logger);
         dialog.present(Some(self));
@@ -299,6 +356,93 @@ impl SharePreviewWindow {
         }
     }
 
+    /// Re-scrape the active URL if it changed since the last check
+    ///
+    /// Pages are fetched again with fresh images, and the card and log are
+    /// updated in place without going through the loading page.
+    ///
+    fn watch_tick(&self) {
+        let imp = self.imp();
+        let active_url = imp.active_url.borrow().to_string();
+
+        // Skip if there is nothing to watch or a scrape is already running
+        if active_url.is_empty() || imp.watch_busy.get() || !imp.url_box.is_sensitive() {
+            return;
+        }
+
+        if let Ok(url) = Url::parse(&active_url) {
+            imp.watch_busy.set(true);
+
+            let spawn = clone!(
+                #[weak(rename_to = win)]
+                self,
+                move || {
+                    spawn!(async move {
+                        let imp = win.imp();
+
+                        // Pages without validators are scraped again less often
+                        let version = page_version(&url).await.ok().flatten();
+                        let changed = match (&version, &*imp.watch_version.borrow()) {
+                            (Some(new), Some(old)) => new != old,
+                            (Some(_), None) => true,
+                            (None, _) => imp.watch_scraped.get().is_none_or(|scraped| {
+                                scraped.elapsed() >= Duration::from_secs(WATCH_UNVALIDATED_INTERVAL)
+                            })
+                        };
+
+                        // Watching needs the latest page and images, skip the cache
+                        if changed {
+                            if let Ok(data) = Data::from_url(&url, true).await {
+                                // The user could have run another URL in the meantime
+                                let same_url = *imp.active_url.borrow() == url.to_string();
+                                let replaced = match (same_url, imp.data.try_borrow_mut()) {
+                                    (true, Ok(mut current)) => {
+                                        *current = data;
+                                        true
+                                    },
+                                    _ => false
+                                };
+
+                                if replaced {
+                                    imp.watch_version.replace(version);
+                                    imp.watch_scraped.set(Some(Instant::now()));
+                                    win.update_card().await;
+                                }
+                            }
+                        }
+
+                        imp.watch_busy.set(false);
+                    });
+                }
+            );
+            spawn();
+        }
+    }
+
+    #[template_callback]
+    fn on_watch_toggled(&self, button: &gtk::ToggleButton) {
+        let imp = self.imp();
+
+        if button.is_active() {
+            let source = glib::timeout_add_seconds_local(
+                WATCH_INTERVAL,
+                clone!(
+                    #[weak(rename_to = win)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        win.watch_tick();
+                        glib::ControlFlow::Continue
+                    }
+                )
+            );
+            imp.watch_source.replace(Some(source));
+        } else if let Some(source) = imp.watch_source.take() {
+            source.remove();
+        }
+    }
+
     #[template_callback]
     fn on_url_entry_changed(&self, entry: &gtk::Entry) {
         if entry.text().is_empty() {
@@ -363,7 +507,8 @@ impl SharePreviewWindow {
             .unwrap()
             .string();
         let social = Social::from_str(string).unwrap();
-        let data = self.imp().data.borrow();
+        // Don't hold the borrow while waiting, a watch refresh could replace the data
+        let data = self.imp().data.borrow().clone();
         let card = data.get_card(social, &self.imp().logger).await;
 
         let card = match card {
@@ -371,11 +516,20 @@ impl SharePreviewWindow {
             Err(error) => CardBox::new_from_error(&error)
         };
 
+        // Keep the scroll position when the card is replaced by a watch refresh
+        let adjustment = self.imp().card_scroll.vadjustment();
+        let scroll = adjustment.value();
+
         let old_card = self.imp().card.replace(Some(card));
         if let Some(c) = old_card {
             self.imp().cardbox.remove(&c);
         }
 
         self.imp().cardbox.prepend(self.imp().card.borrow().as_ref().unwrap());
+
+        // The new card has to be allocated before the old position is valid again
+        glib::idle_add_local_once(move || {
+            adjustment.set_value(scroll);
+        });
     }
 }